@@ -0,0 +1,82 @@
+use std::fmt::{Display, Formatter};
+use std::io;
+
+#[derive(Debug)]
+pub enum CalibrationError {
+    NoDigit(usize),
+    InvalidUtf8(usize),
+    Io(usize, io::Error),
+    Overflow(usize),
+}
+
+impl CalibrationError {
+    pub fn from_read(line_number: usize, error: io::Error) -> Self {
+        match error.kind() {
+            io::ErrorKind::InvalidData => CalibrationError::InvalidUtf8(line_number),
+            _ => CalibrationError::Io(line_number, error),
+        }
+    }
+
+    // Reading stops after an IO failure, and a total that overflowed is already wrong,
+    // so neither can be skipped like a bad line
+    pub fn is_fatal(&self) -> bool {
+        matches!(
+            self,
+            CalibrationError::Io(_, _) | CalibrationError::Overflow(_)
+        )
+    }
+}
+
+impl Display for CalibrationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationError::NoDigit(line) => write!(f, "line {}: no digit found", line),
+            CalibrationError::InvalidUtf8(line) => write!(f, "line {}: invalid UTF-8", line),
+            CalibrationError::Io(line, e) => write!(f, "line {}: could not read: {}", line, e),
+            CalibrationError::Overflow(line) => write!(f, "line {}: total overflowed", line),
+        }
+    }
+}
+
+pub fn report_skipped(skipped: &[CalibrationError]) {
+    if skipped.is_empty() {
+        return;
+    }
+    eprintln!("Skipped {} lines:", skipped.len());
+    for error in skipped {
+        eprintln!("  {}", error);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::CalibrationError;
+    use std::io;
+
+    #[test]
+    fn test_from_read_invalid_utf8() {
+        let error = io::Error::new(
+            io::ErrorKind::InvalidData,
+            "stream did not contain valid UTF-8",
+        );
+        let result = CalibrationError::from_read(4, error);
+        assert!(matches!(result, CalibrationError::InvalidUtf8(4)));
+        assert!(!result.is_fatal());
+    }
+
+    #[test]
+    fn test_from_read_io() {
+        let error = io::Error::new(io::ErrorKind::BrokenPipe, "pipe closed");
+        let result = CalibrationError::from_read(2, error);
+        assert!(matches!(result, CalibrationError::Io(2, _)));
+        assert!(result.is_fatal());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            CalibrationError::NoDigit(7).to_string(),
+            "line 7: no digit found"
+        );
+    }
+}
@@ -1,21 +1,157 @@
-use regex::{Match, Regex};
-use std::io;
+mod error;
+mod numbers;
+mod policy;
+
+use crate::error::{report_skipped, CalibrationError};
+use crate::numbers::{NumberKinds, NumberRegex};
+use crate::policy::CalibrationPolicy;
+use clap::Parser;
+use std::io::Error;
+use std::{io, process};
+
+#[derive(Parser)]
+struct Args {
+    /// Also count English number words from "zero" to "ninety-nine"
+    #[arg(long)]
+    compound: bool,
+    /// Abort on the first line without a digit instead of skipping it
+    #[arg(long)]
+    strict: bool,
+    /// Also count Arabic-Indic, Devanagari and fullwidth digits
+    #[arg(long)]
+    unicode_digits: bool,
+    /// Also count standalone upper-case Roman numerals I to IX
+    #[arg(long)]
+    roman: bool,
+    /// How a line becomes a value: first-last, sum, ends:K or largest-pair
+    #[arg(long, default_value_t = CalibrationPolicy::FirstLast)]
+    policy: CalibrationPolicy,
+}
 
 fn main() {
+    let args = Args::parse();
     let input = io::stdin().lines();
-    let mut total: usize = 0;
-    let number_regex = Regex::new(r"[0-9]").unwrap();
-    for line in input {
-        let interim_line = line.unwrap().trim().to_string();
-        let digits: Vec<Match> = number_regex.find_iter(interim_line.as_str()).collect();
-        if digits.len() > 0 {
-            let interim = format!(
-                "{}{}",
-                digits.first().unwrap().as_str(),
-                digits.last().unwrap().as_str(),
-            );
-            total += interim.parse::<usize>().unwrap();
+    let number_regex = NumberRegex::new(&NumberKinds {
+        compound: args.compound,
+        unicode_digits: args.unicode_digits,
+        roman: args.roman,
+    });
+    let mut total: u128 = 0;
+    let mut skipped = Vec::new();
+    for (i, line) in input.enumerate() {
+        let value = extract_numbers_from_line(i + 1, line, &number_regex, &args.policy);
+        match value.and_then(|value| {
+            total
+                .checked_add(value as u128)
+                .ok_or(CalibrationError::Overflow(i + 1))
+        }) {
+            Ok(sum) => total = sum,
+            Err(e) if args.strict || e.is_fatal() => {
+                report_skipped(&skipped);
+                eprintln!("{}", e);
+                process::exit(1);
+            }
+            Err(e) => skipped.push(e),
         }
     }
     println!("{}", total);
+    report_skipped(&skipped);
+}
+
+fn extract_numbers_from_line(
+    line_number: usize,
+    line: Result<String, Error>,
+    number_regex: &NumberRegex,
+    policy: &CalibrationPolicy,
+) -> Result<usize, CalibrationError> {
+    let interim_line = line.map_err(|e| CalibrationError::from_read(line_number, e))?;
+    let interim_line = interim_line.trim();
+    // First and last only need the two ends of the line, other policies need every token
+    let value = match policy {
+        CalibrationPolicy::FirstLast => match (
+            number_regex.first_token(interim_line),
+            number_regex.last_token(interim_line),
+        ) {
+            (Some(first), Some(last)) => Some(first.first_digit() * 10 + last.last_digit()),
+            _ => None,
+        },
+        _ => policy.apply(&number_regex.tokens(interim_line)),
+    };
+    value.ok_or(CalibrationError::NoDigit(line_number))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::CalibrationError;
+    use crate::numbers::{NumberKinds, NumberRegex};
+    use crate::policy::CalibrationPolicy;
+
+    #[test]
+    fn test_two_numbers() {
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("f96xhv".to_string()),
+            &NumberRegex::new(&NumberKinds::default()),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 96)
+    }
+
+    #[test]
+    fn test_one_number() {
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("d6".to_string()),
+            &NumberRegex::new(&NumberKinds::default()),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 66)
+    }
+
+    #[test]
+    fn test_words_ignored() {
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("one2three".to_string()),
+            &NumberRegex::new(&NumberKinds::default()),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 22)
+    }
+
+    #[test]
+    fn test_compound_words() {
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("seventeenine".to_string()),
+            &NumberRegex::new(&NumberKinds {
+                compound: true,
+                ..NumberKinds::default()
+            }),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 19)
+    }
+
+    #[test]
+    fn test_no_digit() {
+        let result = super::extract_numbers_from_line(
+            5,
+            Ok("one".to_string()),
+            &NumberRegex::new(&NumberKinds::default()),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert!(matches!(result, Err(CalibrationError::NoDigit(5))))
+    }
+
+    #[test]
+    fn test_policy_sum() {
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("a1b2c3d4e5f".to_string()),
+            &NumberRegex::new(&NumberKinds::default()),
+            &CalibrationPolicy::Sum,
+        );
+        assert_eq!(result.unwrap(), 15)
+    }
 }
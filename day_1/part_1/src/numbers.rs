@@ -0,0 +1,251 @@
+use regex_automata::meta::Regex;
+use regex_automata::{Anchored, Input, Match};
+
+static UNITS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+static TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+static TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+static ROMAN: [&str; 9] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"];
+// Zero of the Arabic-Indic, Extended Arabic-Indic, Devanagari and fullwidth digit blocks
+static DIGIT_BLOCKS: [u32; 4] = [0x0660, 0x06F0, 0x0966, 0xFF10];
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub value: usize,
+}
+
+// A multi-digit token contributes its most significant digit when it comes first in a line
+// and its least significant digit when it comes last, so "seventeen" on its own is 17
+impl Token {
+    pub fn first_digit(&self) -> usize {
+        let mut digit = self.value;
+        while digit >= 10 {
+            digit /= 10;
+        }
+        digit
+    }
+
+    pub fn last_digit(&self) -> usize {
+        self.value % 10
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NumberKinds {
+    pub compound: bool,
+    pub unicode_digits: bool,
+    pub roman: bool,
+}
+
+pub struct NumberRegex {
+    regex: Regex,
+    // The value of each pattern, or None for the digit class, whose value is the digit's
+    values: Vec<Option<usize>>,
+    longest: usize,
+}
+
+impl NumberRegex {
+    pub fn new(kinds: &NumberKinds) -> Self {
+        let mut words: Vec<(String, usize)> = Vec::new();
+        if kinds.compound {
+            words.push(("zero".to_string(), 0));
+            words.extend(UNITS.iter().zip(1..).map(|(w, v)| (w.to_string(), v)));
+            words.extend(TEENS.iter().zip(10..).map(|(w, v)| (w.to_string(), v)));
+            for (tens, tens_value) in TENS.iter().zip((20..).step_by(10)) {
+                words.push((tens.to_string(), tens_value));
+                words.extend(
+                    UNITS
+                        .iter()
+                        .zip(1..)
+                        .map(|(unit, value)| (format!("{}-{}", tens, unit), tens_value + value)),
+                );
+            }
+        }
+        // The search is leftmost-first, so longer words go first to win at the same start.
+        // Each word is its own pattern, so a match maps back to its value whatever case
+        // folding let it match
+        words.sort_by_key(|(word, _)| usize::MAX - word.len());
+        let mut longest = words.iter().map(|(word, _)| word.len()).max().unwrap_or(1);
+        let mut patterns: Vec<(String, Option<usize>)> = words
+            .into_iter()
+            .map(|(word, value)| (format!("(?i){}", word), Some(value)))
+            .collect();
+        if kinds.roman {
+            // Roman numerals only count in upper case and when not joined to letters or digits
+            let mut numerals: Vec<(&str, usize)> = ROMAN.iter().copied().zip(1..).collect();
+            numerals.sort_by_key(|(numeral, _)| usize::MAX - numeral.len());
+            patterns.extend(
+                numerals
+                    .into_iter()
+                    .map(|(numeral, value)| (format!(r"\b{}\b", numeral), Some(value))),
+            );
+            longest = longest.max(4);
+        }
+        if kinds.unicode_digits {
+            let ranges: String = DIGIT_BLOCKS
+                .iter()
+                .map(|block| format!(r"\x{{{:X}}}-\x{{{:X}}}", block, block + 9))
+                .collect();
+            patterns.push((format!("[0-9{}]", ranges), None));
+            longest = longest.max(3);
+        } else {
+            patterns.push(("[0-9]".to_string(), None));
+        }
+        Self {
+            regex: Regex::new_many(&patterns.iter().map(|(p, _)| p).collect::<Vec<_>>())
+                .expect("Could not build number regex"),
+            values: patterns.into_iter().map(|(_, value)| value).collect(),
+            longest,
+        }
+    }
+
+    // Tokens are ordered by where they start, and the longest token wins at any one start.
+    // A token lying entirely inside a longer one, like "seven" in "seventeen", does not count
+    pub fn first_token(&self, line: &str) -> Option<Token> {
+        self.regex.find(line).map(|m| self.token(line, m))
+    }
+
+    pub fn last_token(&self, line: &str) -> Option<Token> {
+        (0..line.len())
+            .rev()
+            .filter_map(|start| self.token_at(line, start))
+            .find(|token| !self.is_covered(line, token))
+    }
+
+    pub fn tokens(&self, line: &str) -> Vec<Token> {
+        let mut furthest = 0;
+        let mut out = Vec::new();
+        for token in (0..line.len()).filter_map(|start| self.token_at(line, start)) {
+            if token.end > furthest {
+                furthest = token.end;
+                out.push(token);
+            }
+        }
+        out
+    }
+
+    fn is_covered(&self, line: &str, token: &Token) -> bool {
+        (token.start.saturating_sub(self.longest)..token.start)
+            .filter_map(|start| self.token_at(line, start))
+            .any(|other| other.end >= token.end)
+    }
+
+    fn token_at(&self, line: &str, start: usize) -> Option<Token> {
+        let input = Input::new(line).range(start..).anchored(Anchored::Yes);
+        self.regex.find(input).map(|m| self.token(line, m))
+    }
+
+    fn token(&self, line: &str, m: Match) -> Token {
+        let value = self.values[m.pattern()].unwrap_or_else(|| {
+            line[m.start()..]
+                .chars()
+                .next()
+                .and_then(digit_value)
+                .unwrap_or(0)
+        });
+        Token {
+            start: m.start(),
+            end: m.end(),
+            value,
+        }
+    }
+}
+
+fn digit_value(character: char) -> Option<usize> {
+    let code = character as u32;
+    std::iter::once(u32::from('0'))
+        .chain(DIGIT_BLOCKS)
+        .find(|block| (*block..*block + 10).contains(&code))
+        .map(|block| (code - block) as usize)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::numbers::{NumberKinds, NumberRegex};
+
+    fn compound() -> NumberKinds {
+        NumberKinds {
+            compound: true,
+            ..NumberKinds::default()
+        }
+    }
+
+    #[test]
+    fn test_digits_only() {
+        let regex = NumberRegex::new(&NumberKinds::default());
+        assert_eq!(regex.first_token("xone2three4").map(|t| t.value), Some(2));
+        assert_eq!(regex.last_token("xone2three4x").map(|t| t.value), Some(4));
+    }
+
+    #[test]
+    fn test_compound_ambiguous_overlap() {
+        let regex = NumberRegex::new(&compound());
+        assert_eq!(regex.first_token("seventeenine").map(|t| t.value), Some(17));
+        assert_eq!(regex.last_token("seventeenine").map(|t| t.value), Some(9));
+    }
+
+    #[test]
+    fn test_compound_contained_token() {
+        let regex = NumberRegex::new(&compound());
+        assert_eq!(regex.last_token("xTwenty-One").map(|t| t.value), Some(21));
+        assert_eq!(regex.last_token("xfourteen").map(|t| t.value), Some(14));
+    }
+
+    #[test]
+    fn test_unicode_digits_mixed_script() {
+        let regex = NumberRegex::new(&NumberKinds {
+            unicode_digits: true,
+            ..NumberKinds::default()
+        });
+        assert_eq!(regex.first_token("x٣4").map(|t| t.value), Some(3));
+        assert_eq!(regex.last_token("5x１२x").map(|t| t.value), Some(2));
+    }
+
+    #[test]
+    fn test_roman_numerals() {
+        let regex = NumberRegex::new(&NumberKinds {
+            roman: true,
+            ..NumberKinds::default()
+        });
+        assert_eq!(
+            regex.first_token("vi VIVID VII, 3").map(|t| t.value),
+            Some(7)
+        );
+        assert_eq!(regex.last_token("IX and IV.").map(|t| t.value), Some(4));
+        assert_eq!(regex.last_token("IX and XIV"), regex.first_token("IX"));
+    }
+
+    #[test]
+    fn test_compound_case_folding() {
+        let regex = NumberRegex::new(&compound());
+        assert_eq!(regex.first_token("xſevenx").map(|t| t.value), Some(7));
+        assert_eq!(regex.last_token("xſevenx").map(|t| t.value), Some(7));
+    }
+
+    #[test]
+    fn test_tokens_compound() {
+        let regex = NumberRegex::new(&compound());
+        let result: Vec<usize> = regex
+            .tokens("seventeenine twenty-one")
+            .iter()
+            .map(|t| t.value)
+            .collect();
+        assert_eq!(result, vec![17, 9, 21])
+    }
+}
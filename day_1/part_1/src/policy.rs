@@ -0,0 +1,153 @@
+use crate::numbers::Token;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// Up to nine digits from each end keeps one line's value within a u64. Totals of such
+// values are kept in a u128 and checked, since a u64 total overflows within a few lines
+const MAX_ENDS: usize = 9;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum CalibrationPolicy {
+    FirstLast,
+    Sum,
+    Ends(usize),
+    LargestPair,
+}
+
+impl CalibrationPolicy {
+    // Every token stands for the digits of its value, so "twenty-one" adds both 2 and 1
+    pub fn apply(&self, tokens: &[Token]) -> Option<usize> {
+        let digits: Vec<usize> = tokens
+            .iter()
+            .flat_map(|t| t.value.to_string().into_bytes())
+            .map(|b| (b - b'0') as usize)
+            .collect();
+        if digits.is_empty() {
+            return None;
+        }
+        let value = match self {
+            CalibrationPolicy::FirstLast => digits[0] * 10 + digits[digits.len() - 1],
+            CalibrationPolicy::Sum => digits.iter().sum(),
+            CalibrationPolicy::Ends(k) => {
+                let k = (*k).min(digits.len());
+                digits[..k]
+                    .iter()
+                    .chain(&digits[digits.len() - k..])
+                    .fold(0, |value, digit| value * 10 + digit)
+            }
+            // A line with a single digit pairs it with itself, as first and last would
+            CalibrationPolicy::LargestPair if digits.len() == 1 => digits[0] * 11,
+            CalibrationPolicy::LargestPair => (0..digits.len())
+                .flat_map(|i| (i + 1..digits.len()).map(move |j| (i, j)))
+                .map(|(i, j)| digits[i] * 10 + digits[j])
+                .max()
+                .unwrap_or(0),
+        };
+        Some(value)
+    }
+}
+
+impl FromStr for CalibrationPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "first-last" => Ok(CalibrationPolicy::FirstLast),
+            "sum" => Ok(CalibrationPolicy::Sum),
+            "largest-pair" => Ok(CalibrationPolicy::LargestPair),
+            _ => match s.strip_prefix("ends:").map(|k| k.parse::<usize>()) {
+                Some(Ok(k)) if (1..=MAX_ENDS).contains(&k) => Ok(CalibrationPolicy::Ends(k)),
+                Some(_) => Err(format!("ends takes a digit count from 1 to {}", MAX_ENDS)),
+                None => Err(format!(
+                    "unknown policy {:?}, expected first-last, sum, ends:K or largest-pair",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+impl Display for CalibrationPolicy {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CalibrationPolicy::FirstLast => write!(f, "first-last"),
+            CalibrationPolicy::Sum => write!(f, "sum"),
+            CalibrationPolicy::Ends(k) => write!(f, "ends:{}", k),
+            CalibrationPolicy::LargestPair => write!(f, "largest-pair"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::numbers::Token;
+    use crate::policy::CalibrationPolicy;
+    use std::str::FromStr;
+
+    fn tokens(values: &[usize]) -> Vec<Token> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| Token {
+                start: i,
+                end: i + 1,
+                value: *value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_first_last() {
+        let result = CalibrationPolicy::FirstLast.apply(&tokens(&[3, 17, 21]));
+        assert_eq!(result, Some(31))
+    }
+
+    #[test]
+    fn test_sum() {
+        let result = CalibrationPolicy::Sum.apply(&tokens(&[3, 17, 21]));
+        assert_eq!(result, Some(14))
+    }
+
+    #[test]
+    fn test_ends() {
+        let result = CalibrationPolicy::Ends(2).apply(&tokens(&[3, 17, 21, 5]));
+        assert_eq!(result, Some(3115))
+    }
+
+    #[test]
+    fn test_ends_short_line() {
+        let result = CalibrationPolicy::Ends(3).apply(&tokens(&[4, 2]));
+        assert_eq!(result, Some(4242))
+    }
+
+    #[test]
+    fn test_largest_pair() {
+        let result = CalibrationPolicy::LargestPair.apply(&tokens(&[8, 1, 9, 2]));
+        assert_eq!(result, Some(92))
+    }
+
+    #[test]
+    fn test_largest_pair_single_digit() {
+        let result = CalibrationPolicy::LargestPair.apply(&tokens(&[6]));
+        assert_eq!(result, Some(66))
+    }
+
+    #[test]
+    fn test_no_tokens() {
+        assert_eq!(CalibrationPolicy::Sum.apply(&[]), None)
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(
+            CalibrationPolicy::from_str("ends:4"),
+            Ok(CalibrationPolicy::Ends(4))
+        );
+        assert_eq!(
+            CalibrationPolicy::from_str("largest-pair"),
+            Ok(CalibrationPolicy::LargestPair)
+        );
+        assert!(CalibrationPolicy::from_str("ends:0").is_err());
+        assert!(CalibrationPolicy::from_str("median").is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use crate::error::CalibrationError;
+use crate::policy::CalibrationPolicy;
+use crate::recogniser::{Recogniser, Token};
+use clap::ValueEnum;
+use serde::Serialize;
+
+const FIRST_COLOUR: &str = "\x1b[1;32m";
+const LAST_COLOUR: &str = "\x1b[1;34m";
+const OTHER_COLOUR: &str = "\x1b[33m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ExplainFormat {
+    Text,
+    Json,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq)]
+pub struct TokenReport {
+    pub start: usize,
+    pub end: usize,
+    pub text: String,
+    pub value: usize,
+}
+
+#[derive(Serialize, Debug, Eq, PartialEq)]
+pub struct Explanation {
+    pub line: usize,
+    pub text: String,
+    pub tokens: Vec<TokenReport>,
+    pub first: Option<usize>,
+    pub last: Option<usize>,
+    pub value: usize,
+}
+
+impl Explanation {
+    pub fn new(
+        line_number: usize,
+        line: &str,
+        recogniser: &Recogniser,
+        policy: &CalibrationPolicy,
+    ) -> Result<Self, CalibrationError> {
+        let line = line.trim();
+        let tokens: Vec<Token> = recogniser.tokens(line);
+        let (first, last) = match tokens.len() {
+            0 => (None, None),
+            n => (Some(0), Some(n - 1)),
+        };
+        let value = policy
+            .apply(&tokens)
+            .ok_or(CalibrationError::NoDigit(line_number))?;
+        Ok(Self {
+            line: line_number,
+            text: line.to_string(),
+            tokens: tokens
+                .iter()
+                .map(|t| TokenReport {
+                    start: t.start,
+                    end: t.end,
+                    text: line[t.start..t.end].to_string(),
+                    value: t.value,
+                })
+                .collect(),
+            first,
+            last,
+            value,
+        })
+    }
+
+    pub fn render(&self, format: ExplainFormat) -> String {
+        match format {
+            ExplainFormat::Text => self.to_text(),
+            ExplainFormat::Json => {
+                serde_json::to_string(self).expect("Could not serialise explanation")
+            }
+        }
+    }
+
+    fn to_text(&self) -> String {
+        let mut out = format!("{:>6}: {}\n", self.line, self.highlighted());
+        for (i, token) in self.tokens.iter().enumerate() {
+            let role = if Some(i) == self.first && Some(i) == self.last {
+                " first, last"
+            } else if Some(i) == self.first {
+                " first"
+            } else if Some(i) == self.last {
+                " last"
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "        {}..{} {:?} = {}{}\n",
+                token.start, token.end, token.text, token.value, role
+            ));
+        }
+        out.push_str(&format!("        value {}", self.value));
+        out
+    }
+
+    // Overlapping tokens share characters, so the first token takes precedence, then the last
+    fn highlighted(&self) -> String {
+        let colour_at = |index: usize| {
+            let covers = |token: Option<usize>| {
+                token.is_some_and(|t| (self.tokens[t].start..self.tokens[t].end).contains(&index))
+            };
+            if covers(self.first) {
+                Some(FIRST_COLOUR)
+            } else if covers(self.last) {
+                Some(LAST_COLOUR)
+            } else if self
+                .tokens
+                .iter()
+                .any(|t| (t.start..t.end).contains(&index))
+            {
+                Some(OTHER_COLOUR)
+            } else {
+                None
+            }
+        };
+        let mut out = String::new();
+        let mut current = None;
+        for (index, character) in self.text.char_indices() {
+            let colour = colour_at(index);
+            if colour != current {
+                out.push_str(colour.unwrap_or(RESET));
+                current = colour;
+            }
+            out.push(character);
+        }
+        if current.is_some() {
+            out.push_str(RESET);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::CalibrationError;
+    use crate::explain::{ExplainFormat, Explanation};
+    use crate::policy::CalibrationPolicy;
+    use crate::recogniser::Recogniser;
+    use crate::vocabulary::{Language, Vocabulary};
+
+    #[test]
+    fn test_explain_overlapping() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let result =
+            Explanation::new(3, "1twone", &recogniser, &CalibrationPolicy::FirstLast).unwrap();
+        assert_eq!(result.tokens.len(), 3);
+        assert_eq!(result.tokens[2].text, "one");
+        assert_eq!((result.tokens[2].start, result.tokens[2].end), (3, 6));
+        assert_eq!(result.first, Some(0));
+        assert_eq!(result.last, Some(2));
+        assert_eq!(result.value, 11);
+    }
+
+    #[test]
+    fn test_explain_no_tokens() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let result = Explanation::new(4, "abc", &recogniser, &CalibrationPolicy::FirstLast);
+        assert!(matches!(result, Err(CalibrationError::NoDigit(4))));
+    }
+
+    #[test]
+    fn test_explain_json() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let result = Explanation::new(1, "a7", &recogniser, &CalibrationPolicy::FirstLast)
+            .unwrap()
+            .render(ExplainFormat::Json);
+        assert_eq!(
+            result,
+            r#"{"line":1,"text":"a7","tokens":[{"start":1,"end":2,"text":"7","value":7}],"first":0,"last":0,"value":77}"#
+        )
+    }
+
+    #[test]
+    fn test_explain_highlighted() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let result = Explanation::new(1, "a7b", &recogniser, &CalibrationPolicy::FirstLast)
+            .unwrap()
+            .highlighted();
+        assert_eq!(result, "a\x1b[1;32m7\x1b[0mb")
+    }
+
+    #[test]
+    fn test_explain_policy() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let result = Explanation::new(1, "4nine2", &recogniser, &CalibrationPolicy::Sum).unwrap();
+        assert_eq!(result.value, 15);
+    }
+}
@@ -1,104 +1,332 @@
-use regex::{Match, Regex};
-use std::collections::HashMap;
-use std::io;
+mod error;
+mod explain;
+mod parallel;
+mod policy;
+mod recogniser;
+mod vocabulary;
+
+use crate::error::{report_skipped, CalibrationError};
+use crate::explain::{ExplainFormat, Explanation};
+use crate::policy::CalibrationPolicy;
+use crate::recogniser::Recogniser;
+use crate::vocabulary::{Language, Vocabulary};
+use clap::Parser;
 use std::io::Error;
+use std::path::{Path, PathBuf};
+use std::{io, process};
+
+#[derive(Parser)]
+struct Args {
+    /// Built-in language for number words
+    #[arg(long, value_enum, default_value_t = Language::English)]
+    language: Language,
+    /// TOML or JSON file mapping number words to their digit
+    #[arg(long, conflicts_with = "language")]
+    words: Option<PathBuf>,
+    /// Also recognise "zero", the teens, the tens and compounds such as "twenty-one"
+    #[arg(long)]
+    compound: bool,
+    /// Also recognise Arabic-Indic, Devanagari and fullwidth digits
+    #[arg(long)]
+    unicode_digits: bool,
+    /// Also recognise standalone upper-case Roman numerals I to IX
+    #[arg(long)]
+    roman: bool,
+    /// How a line becomes a value: first-last, sum, ends:K or largest-pair
+    #[arg(long, default_value_t = CalibrationPolicy::FirstLast)]
+    policy: CalibrationPolicy,
+    /// Report every matched token and the chosen first and last token for each line
+    #[arg(long, value_enum)]
+    explain: Option<ExplainFormat>,
+    /// Abort on the first line without a digit instead of skipping it
+    #[arg(long)]
+    strict: bool,
+    /// Memory-map this file and total it across all cores, printing only the total
+    #[arg(long, conflicts_with = "explain")]
+    parallel: Option<PathBuf>,
+}
 
 fn main() {
+    let args = Args::parse();
+    let vocabulary = match args.words {
+        Some(path) => Vocabulary::from_file(&path).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1)
+        }),
+        None => Vocabulary::from_language(args.language),
+    };
+    let vocabulary = if args.compound {
+        vocabulary.with_compounds()
+    } else {
+        vocabulary
+    };
+    let vocabulary = if args.unicode_digits {
+        vocabulary.with_unicode_digits()
+    } else {
+        vocabulary
+    };
+    let vocabulary = if args.roman {
+        vocabulary.with_roman_numerals()
+    } else {
+        vocabulary
+    };
+    let recogniser = Recogniser::new(&vocabulary);
+    if let Some(path) = args.parallel {
+        total_file(&path, &recogniser, &args.policy, args.strict);
+        return;
+    }
     let input = io::stdin().lines();
-    let mut total: usize = 0;
-    for line in input {
-        let i = extract_numbers_from_line(line);
-        println!("{}", i);
-        total += i;
+    if let Some(format) = args.explain {
+        explain_lines(input, &recogniser, &args.policy, format, args.strict);
+        return;
+    }
+    let mut total: u128 = 0;
+    let mut skipped = Vec::new();
+    for (i, line) in input.enumerate() {
+        match extract_numbers_from_line(i + 1, line, &recogniser, &args.policy) {
+            Ok(value) => {
+                println!("{}", value);
+                total = total
+                    .checked_add(value as u128)
+                    .unwrap_or_else(|| overflow(i + 1, &skipped));
+            }
+            Err(e) => skip_or_abort(e, &mut skipped, args.strict),
+        }
     }
     println!("{}", total);
+    report_skipped(&skipped);
 }
 
-fn extract_numbers_from_line(line: Result<String, Error>) -> usize {
-    let number_regexes = [
-        Regex::new(r"[0-9]").unwrap(),
-        Regex::new(r"one").unwrap(),
-        Regex::new(r"two").unwrap(),
-        Regex::new(r"three").unwrap(),
-        Regex::new(r"four").unwrap(),
-        Regex::new(r"five").unwrap(),
-        Regex::new(r"six").unwrap(),
-        Regex::new(r"seven").unwrap(),
-        Regex::new(r"eight").unwrap(),
-        Regex::new(r"nine").unwrap(),
-    ];
-    let number_map: HashMap<&str, String> = HashMap::from([
-        ("one", "1".to_string()),
-        ("two", "2".to_string()),
-        ("three", "3".to_string()),
-        ("four", "4".to_string()),
-        ("five", "5".to_string()),
-        ("six", "6".to_string()),
-        ("seven", "7".to_string()),
-        ("eight", "8".to_string()),
-        ("nine", "9".to_string()),
-    ]);
-    let interim_line = line.unwrap().trim().to_string().to_lowercase();
-    let mut found_digits: Vec<Match> = number_regexes
-        .iter()
-        .flat_map(|r| r.find_iter(interim_line.as_str()))
-        .collect();
-    found_digits.sort_by(|m1, m2| m1.start().partial_cmp(&m2.start()).unwrap());
-
-    if found_digits.len() > 0 {
-        let mut digits = [
-            found_digits.first().unwrap().as_str(),
-            found_digits.last().unwrap().as_str(),
-        ];
-        for index in 0..2 {
-            if number_map.contains_key(digits[index]) {
-                digits[index] = number_map[digits[index]].as_str();
-            }
+fn skip_or_abort(error: CalibrationError, skipped: &mut Vec<CalibrationError>, strict: bool) {
+    if strict || error.is_fatal() {
+        report_skipped(skipped);
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+    skipped.push(error);
+}
+
+fn overflow(line_number: usize, skipped: &[CalibrationError]) -> ! {
+    report_skipped(skipped);
+    eprintln!("{}", CalibrationError::Overflow(line_number));
+    process::exit(1);
+}
+
+fn total_file(path: &Path, recogniser: &Recogniser, policy: &CalibrationPolicy, strict: bool) {
+    let data = parallel::map_file(path).unwrap_or_else(|e| {
+        eprintln!("could not map {}: {}", path.display(), e);
+        process::exit(1)
+    });
+    let (total, skipped) = parallel::total_parallel(
+        &data,
+        recogniser,
+        policy,
+        parallel::default_chunk_size(data.len()),
+    );
+    if let Some(error) = skipped.iter().find(|e| strict || e.is_fatal()) {
+        eprintln!("{}", error);
+        process::exit(1);
+    }
+    println!("{}", total);
+    report_skipped(&skipped);
+}
+
+fn explain_lines(
+    input: impl Iterator<Item = Result<String, Error>>,
+    recogniser: &Recogniser,
+    policy: &CalibrationPolicy,
+    format: ExplainFormat,
+    strict: bool,
+) {
+    let mut total: u128 = 0;
+    let mut skipped = Vec::new();
+    for (i, line) in input.enumerate() {
+        match line {
+            Ok(line) => match Explanation::new(i + 1, &line, recogniser, policy) {
+                Ok(explanation) => {
+                    println!("{}", explanation.render(format));
+                    total = total
+                        .checked_add(explanation.value as u128)
+                        .unwrap_or_else(|| overflow(i + 1, &skipped));
+                }
+                Err(e) => skip_or_abort(e, &mut skipped, strict),
+            },
+            Err(e) => skip_or_abort(CalibrationError::from_read(i + 1, e), &mut skipped, strict),
         }
-        return format!("{}{}", digits[0], digits[1],)
-            .parse::<usize>()
-            .unwrap();
     }
-    0
+    match format {
+        ExplainFormat::Text => println!("{}", total),
+        ExplainFormat::Json => println!("{}", serde_json::json!({ "total": total })),
+    }
+    report_skipped(&skipped);
+}
+
+fn extract_numbers_from_line(
+    line_number: usize,
+    line: Result<String, Error>,
+    recogniser: &Recogniser,
+    policy: &CalibrationPolicy,
+) -> Result<usize, CalibrationError> {
+    let interim_line = line.map_err(|e| CalibrationError::from_read(line_number, e))?;
+    recogniser
+        .calibration_value(interim_line.trim(), policy)
+        .ok_or(CalibrationError::NoDigit(line_number))
 }
 
 #[cfg(test)]
 mod test {
+    use crate::error::CalibrationError;
+    use crate::policy::CalibrationPolicy;
+    use crate::recogniser::Recogniser;
+    use crate::vocabulary::{Language, Vocabulary};
+    use std::io;
 
     #[test]
     fn test_two_numbers() {
-        let result = super::extract_numbers_from_line(Ok("f96xhv".to_string()));
-        assert_eq!(result, 96)
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("f96xhv".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 96)
     }
 
     #[test]
     fn test_one_number_end_string() {
-        let result = super::extract_numbers_from_line(Ok("5seven7slxxbsjqktseven".to_string()));
-        assert_eq!(result, 57)
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("5seven7slxxbsjqktseven".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 57)
     }
 
     #[test]
     fn test_one_number_start_string() {
-        let result = super::extract_numbers_from_line(Ok("six97".to_string()));
-        assert_eq!(result, 67)
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("six97".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 67)
     }
 
     #[test]
     fn test_one_number() {
-        let result = super::extract_numbers_from_line(Ok("d6".to_string()));
-        assert_eq!(result, 66)
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("d6".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 66)
     }
 
     #[test]
     fn test_overlapping_strings_first() {
-        let result =
-            super::extract_numbers_from_line(Ok("jtwonetwothree5znqsvfour5czgsqvvtgg".to_string()));
-        assert_eq!(result, 25)
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("jtwonetwothree5znqsvfour5czgsqvvtgg".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 25)
     }
 
     #[test]
     fn test_overlapping_strings_last() {
-        let result = super::extract_numbers_from_line(Ok("1twone".to_string()));
-        assert_eq!(result, 11)
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("1twone".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 11)
+    }
+
+    #[test]
+    fn test_compound_ambiguous_overlap() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_compounds());
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("seventeenine".to_string()),
+            &recogniser,
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 19)
+    }
+
+    #[test]
+    fn test_compound_single_word() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_compounds());
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("xfourteenx".to_string()),
+            &recogniser,
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 14)
+    }
+
+    #[test]
+    fn test_compound_hyphenated() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_compounds());
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("zero3ninety-eight".to_string()),
+            &recogniser,
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 8)
+    }
+
+    #[test]
+    fn test_compound_disabled() {
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("xfourteenx".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert_eq!(result.unwrap(), 44)
+    }
+
+    #[test]
+    fn test_no_digit() {
+        let result = super::extract_numbers_from_line(
+            12,
+            Ok("xyz".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert!(matches!(result, Err(CalibrationError::NoDigit(12))))
+    }
+
+    #[test]
+    fn test_invalid_utf8() {
+        let result = super::extract_numbers_from_line(
+            3,
+            Err(io::Error::new(io::ErrorKind::InvalidData, "invalid UTF-8")),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::FirstLast,
+        );
+        assert!(matches!(result, Err(CalibrationError::InvalidUtf8(3))))
+    }
+
+    #[test]
+    fn test_policy_largest_pair() {
+        let result = super::extract_numbers_from_line(
+            1,
+            Ok("3eight1nine2".to_string()),
+            &Recogniser::new(&Vocabulary::from_language(Language::English)),
+            &CalibrationPolicy::LargestPair,
+        );
+        assert_eq!(result.unwrap(), 92)
     }
 }
@@ -0,0 +1,186 @@
+use crate::error::CalibrationError;
+use crate::policy::CalibrationPolicy;
+use crate::recogniser::Recogniser;
+use memmap2::Mmap;
+use rayon::prelude::*;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+const MIN_CHUNK_SIZE: usize = 1 << 20;
+
+pub fn map_file(path: &Path) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+    // Safety: the file is only read, and the map lives no longer than this run
+    unsafe { Mmap::map(&file) }
+}
+
+pub fn total_parallel(
+    data: &[u8],
+    recogniser: &Recogniser,
+    policy: &CalibrationPolicy,
+    chunk_size: usize,
+) -> (u128, Vec<CalibrationError>) {
+    let chunks = split_chunks(data, chunk_size);
+    let line_counts: Vec<usize> = chunks
+        .par_iter()
+        .map(|chunk| chunk.iter().filter(|&&b| b == b'\n').count())
+        .collect();
+    let first_lines: Vec<usize> = line_counts
+        .iter()
+        .scan(1, |line, count| {
+            let first = *line;
+            *line += count;
+            Some(first)
+        })
+        .collect();
+
+    let results: Vec<(u128, Vec<CalibrationError>)> = chunks
+        .par_iter()
+        .zip(&first_lines)
+        .map(|(chunk, first_line)| total_chunk(chunk, *first_line, recogniser, policy))
+        .collect();
+
+    let mut total: u128 = 0;
+    let mut skipped = Vec::new();
+    for ((chunk_total, chunk_skipped), first_line) in results.into_iter().zip(first_lines) {
+        skipped.extend(chunk_skipped);
+        match total.checked_add(chunk_total) {
+            Some(sum) => total = sum,
+            None => {
+                skipped.push(CalibrationError::Overflow(first_line));
+                break;
+            }
+        }
+    }
+    (total, skipped)
+}
+
+pub fn default_chunk_size(length: usize) -> usize {
+    (length / (rayon::current_num_threads() * 4)).max(MIN_CHUNK_SIZE)
+}
+
+// Each chunk ends just after a newline, so no line is split between two chunks
+fn split_chunks(data: &[u8], chunk_size: usize) -> Vec<&[u8]> {
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < data.len() {
+        let end = (start + chunk_size.max(1)).min(data.len());
+        let end = match data[end..].iter().position(|&b| b == b'\n') {
+            Some(i) => end + i + 1,
+            None => data.len(),
+        };
+        chunks.push(&data[start..end]);
+        start = end;
+    }
+    chunks
+}
+
+fn total_chunk(
+    chunk: &[u8],
+    first_line: usize,
+    recogniser: &Recogniser,
+    policy: &CalibrationPolicy,
+) -> (u128, Vec<CalibrationError>) {
+    let mut total: u128 = 0;
+    let mut skipped = Vec::new();
+    for (i, line) in chunk.split_inclusive(|&b| b == b'\n').enumerate() {
+        let line_number = first_line + i;
+        match std::str::from_utf8(line) {
+            Ok(line) => match recogniser.calibration_value(line.trim(), policy) {
+                Some(value) => match total.checked_add(value as u128) {
+                    Some(sum) => total = sum,
+                    None => {
+                        skipped.push(CalibrationError::Overflow(line_number));
+                        break;
+                    }
+                },
+                None => skipped.push(CalibrationError::NoDigit(line_number)),
+            },
+            Err(_) => skipped.push(CalibrationError::InvalidUtf8(line_number)),
+        }
+    }
+    (total, skipped)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::CalibrationError;
+    use crate::parallel::{split_chunks, total_parallel};
+    use crate::policy::CalibrationPolicy;
+    use crate::recogniser::Recogniser;
+    use crate::vocabulary::{Language, Vocabulary};
+
+    #[test]
+    fn test_split_chunks_on_newlines() {
+        let result = split_chunks(b"ab\ncd\nef", 1);
+        assert_eq!(result, vec![&b"ab\n"[..], &b"cd\n"[..], &b"ef"[..]])
+    }
+
+    #[test]
+    fn test_split_chunks_long_line() {
+        let result = split_chunks(b"abcdef\ng\n", 2);
+        assert_eq!(result, vec![&b"abcdef\n"[..], &b"g\n"[..]])
+    }
+
+    #[test]
+    fn test_total_matches_sequential() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let lines = [
+            "two1nine",
+            "eightwothree",
+            "abcone2threexyz",
+            "xtwone3four",
+            "7pqrstsixteen",
+        ];
+        let data: String = lines
+            .iter()
+            .cycle()
+            .take(500)
+            .map(|l| format!("{}\r\n", l))
+            .collect();
+        let sequential: u128 = data
+            .lines()
+            .filter_map(|l| recogniser.calibration_value(l.trim(), &CalibrationPolicy::FirstLast))
+            .map(|value| value as u128)
+            .sum();
+        for chunk_size in [1, 7, 64, 4096] {
+            let (total, skipped) = total_parallel(
+                data.as_bytes(),
+                &recogniser,
+                &CalibrationPolicy::FirstLast,
+                chunk_size,
+            );
+            assert_eq!(total, sequential);
+            assert!(skipped.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_skipped_line_numbers() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let (total, skipped) = total_parallel(
+            b"1\nabc\n2\n\xff\n3",
+            &recogniser,
+            &CalibrationPolicy::FirstLast,
+            1,
+        );
+        assert_eq!(total, 66);
+        assert!(matches!(skipped[0], CalibrationError::NoDigit(2)));
+        assert!(matches!(skipped[1], CalibrationError::InvalidUtf8(4)));
+    }
+
+    #[test]
+    fn test_total_past_u64() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let data = "923456789012345678909\n".repeat(30);
+        let (total, skipped) = total_parallel(
+            data.as_bytes(),
+            &recogniser,
+            &CalibrationPolicy::Ends(9),
+            64,
+        );
+        assert_eq!(total, 923456789345678909 * 30);
+        assert!(skipped.is_empty());
+    }
+}
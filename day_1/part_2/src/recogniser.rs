@@ -0,0 +1,400 @@
+use crate::policy::CalibrationPolicy;
+use crate::vocabulary::Vocabulary;
+use aho_corasick::{AhoCorasick, Anchored, Input, Match, MatchKind, StartKind};
+
+static DIGITS: [(&str, usize); 10] = [
+    ("0", 0),
+    ("1", 1),
+    ("2", 2),
+    ("3", 3),
+    ("4", 4),
+    ("5", 5),
+    ("6", 6),
+    ("7", 7),
+    ("8", 8),
+    ("9", 9),
+];
+
+#[derive(Debug, Eq, PartialEq, Clone, Copy)]
+pub struct Token {
+    pub start: usize,
+    pub end: usize,
+    pub value: usize,
+}
+
+// A multi-digit token contributes its most significant digit when it comes first in a line
+// and its least significant digit when it comes last, so "seventeen" on its own is 17
+impl Token {
+    pub fn first_digit(&self) -> usize {
+        let mut digit = self.value;
+        while digit >= 10 {
+            digit /= 10;
+        }
+        digit
+    }
+
+    pub fn last_digit(&self) -> usize {
+        self.value % 10
+    }
+}
+
+pub struct Recogniser {
+    automaton: AhoCorasick,
+    values: Vec<usize>,
+    longest: usize,
+    roman_from: usize,
+}
+
+impl Recogniser {
+    pub fn new(vocabulary: &Vocabulary) -> Self {
+        let patterns: Vec<(String, usize)> = DIGITS
+            .iter()
+            .map(|(w, v)| (w.to_string(), *v))
+            .chain(
+                vocabulary
+                    .words()
+                    .iter()
+                    .flat_map(|(w, v)| case_variants(w).into_iter().map(move |w| (w, *v))),
+            )
+            .chain(vocabulary.roman().iter().map(|(w, v)| (w.clone(), *v)))
+            .collect();
+        // Both start kinds are needed: the forward scan is unanchored, the backward scan anchored
+        let automaton = AhoCorasick::builder()
+            .match_kind(MatchKind::LeftmostLongest)
+            .start_kind(StartKind::Both)
+            .ascii_case_insensitive(true)
+            .build(patterns.iter().map(|(pattern, _)| pattern))
+            .expect("Could not build number automaton");
+        Self {
+            automaton,
+            values: patterns.iter().map(|(_, value)| *value).collect(),
+            longest: patterns.iter().map(|(p, _)| p.len()).max().unwrap_or(0),
+            roman_from: patterns.len() - vocabulary.roman().len(),
+        }
+    }
+
+    // Tokens are ordered by where they start, and the longest token wins at any one start.
+    // A token lying entirely inside a longer one, like "seven" in "seventeen", does not count
+    pub fn first_token(&self, line: &str) -> Option<Token> {
+        let mut start = 0;
+        while let Some(m) = self.automaton.find(Input::new(line).range(start..)) {
+            if let Some(token) = self.token(line, m) {
+                return Some(token);
+            }
+            start = m.start() + 1;
+        }
+        None
+    }
+
+    pub fn last_token(&self, line: &str) -> Option<Token> {
+        (0..line.len())
+            .rev()
+            .filter_map(|start| self.token_at(line, start))
+            .find(|token| !self.is_covered(line, token))
+    }
+
+    // First and last only need the two ends of the line, other policies need every token
+    pub fn calibration_value(&self, line: &str, policy: &CalibrationPolicy) -> Option<usize> {
+        match policy {
+            CalibrationPolicy::FirstLast => match (self.first_token(line), self.last_token(line)) {
+                (Some(first), Some(last)) => Some(first.first_digit() * 10 + last.last_digit()),
+                _ => None,
+            },
+            _ => policy.apply(&self.tokens(line)),
+        }
+    }
+
+    pub fn tokens(&self, line: &str) -> Vec<Token> {
+        let mut furthest = 0;
+        let mut out = Vec::new();
+        for token in (0..line.len()).filter_map(|start| self.token_at(line, start)) {
+            if token.end > furthest {
+                furthest = token.end;
+                out.push(token);
+            }
+        }
+        out
+    }
+
+    fn is_covered(&self, line: &str, token: &Token) -> bool {
+        (token.start.saturating_sub(self.longest)..token.start)
+            .filter_map(|start| self.token_at(line, start))
+            .any(|other| other.end >= token.end)
+    }
+
+    fn token_at(&self, line: &str, start: usize) -> Option<Token> {
+        let input = Input::new(line).range(start..).anchored(Anchored::Yes);
+        self.automaton.find(input).and_then(|m| self.token(line, m))
+    }
+
+    fn token(&self, line: &str, m: Match) -> Option<Token> {
+        if m.pattern().as_usize() >= self.roman_from
+            && !is_standalone_upper(line, m.start(), m.end())
+        {
+            return None;
+        }
+        Some(Token {
+            start: m.start(),
+            end: m.end(),
+            value: self.values[m.pattern()],
+        })
+    }
+}
+
+// The automaton only folds ASCII case, so a word with other letters also needs its upper
+// and title case spelt out. Matching the line as written keeps offsets right for --explain
+fn case_variants(word: &str) -> Vec<String> {
+    let mut out = vec![word.to_string()];
+    if !word.is_ascii() {
+        let mut characters = word.chars();
+        let title: String = characters
+            .next()
+            .map(|first| first.to_uppercase().chain(characters).collect())
+            .unwrap_or_default();
+        for variant in [word.to_uppercase(), title] {
+            if !out.contains(&variant) {
+                out.push(variant);
+            }
+        }
+    }
+    out
+}
+
+fn is_standalone_upper(line: &str, start: usize, end: usize) -> bool {
+    let joined = |c: char| c.is_alphanumeric() || c == '_';
+    line[start..end].chars().all(|c| c.is_ascii_uppercase())
+        && !line[..start].chars().next_back().is_some_and(joined)
+        && !line[end..].chars().next().is_some_and(joined)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::policy::CalibrationPolicy;
+    use crate::recogniser::{Recogniser, Token};
+    use crate::vocabulary::{Language, Vocabulary};
+
+    #[test]
+    fn test_first_token_word() {
+        let result =
+            Recogniser::new(&Vocabulary::from_language(Language::English)).first_token("xtwone3");
+        assert_eq!(
+            result,
+            Some(Token {
+                start: 1,
+                end: 4,
+                value: 2
+            })
+        )
+    }
+
+    #[test]
+    fn test_last_token_overlapping() {
+        let result =
+            Recogniser::new(&Vocabulary::from_language(Language::English)).last_token("xtwone3x");
+        assert_eq!(
+            result,
+            Some(Token {
+                start: 6,
+                end: 7,
+                value: 3
+            })
+        )
+    }
+
+    #[test]
+    fn test_last_token_overlapping_word() {
+        let result =
+            Recogniser::new(&Vocabulary::from_language(Language::English)).last_token("xtwonex");
+        assert_eq!(
+            result,
+            Some(Token {
+                start: 3,
+                end: 6,
+                value: 1
+            })
+        )
+    }
+
+    #[test]
+    fn test_non_ascii_case() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::German));
+        assert_eq!(recogniser.first_token("xFÜNFx").map(|t| t.value), Some(5));
+        assert_eq!(recogniser.last_token("xFünfx").map(|t| t.value), Some(5));
+        assert_eq!(recogniser.first_token("xfünfx").map(|t| t.value), Some(5));
+    }
+
+    #[test]
+    fn test_tokens_case_insensitive() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        assert_eq!(recogniser.first_token("SeVen").map(|t| t.value), Some(7));
+        assert_eq!(recogniser.last_token("SeVen").map(|t| t.value), Some(7));
+    }
+
+    #[test]
+    fn test_no_tokens() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        assert_eq!(recogniser.first_token("abc"), None);
+        assert_eq!(recogniser.last_token("abc"), None);
+    }
+
+    #[test]
+    fn test_tokens_french() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::French));
+        assert_eq!(recogniser.first_token("xdeuxone").map(|t| t.value), Some(2));
+        assert_eq!(
+            recogniser.last_token("quatrexneufx").map(|t| t.value),
+            Some(9)
+        );
+    }
+
+    #[test]
+    fn test_tokens_german_non_ascii() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::German));
+        assert_eq!(
+            recogniser.first_token("xfünfzwei").map(|t| t.value),
+            Some(5)
+        );
+        assert_eq!(recogniser.last_token("zweifünfx").map(|t| t.value), Some(5));
+    }
+
+    #[test]
+    fn test_token_digits() {
+        let token = Token {
+            start: 0,
+            end: 9,
+            value: 17,
+        };
+        assert_eq!(token.first_digit(), 1);
+        assert_eq!(token.last_digit(), 7);
+    }
+
+    #[test]
+    fn test_compound_longest_first() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_compounds());
+        assert_eq!(
+            recogniser.first_token("seventeenine").map(|t| t.value),
+            Some(17)
+        );
+        assert_eq!(
+            recogniser.last_token("seventeenine").map(|t| t.value),
+            Some(9)
+        );
+    }
+
+    #[test]
+    fn test_compound_contained_token() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_compounds());
+        assert_eq!(
+            recogniser.last_token("xtwenty-one").map(|t| t.value),
+            Some(21)
+        );
+        assert_eq!(
+            recogniser.last_token("xfourteen").map(|t| t.value),
+            Some(14)
+        );
+    }
+
+    #[test]
+    fn test_tokens_overlapping() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        let result: Vec<(usize, usize)> = recogniser
+            .tokens("1twone")
+            .iter()
+            .map(|t| (t.start, t.value))
+            .collect();
+        assert_eq!(result, vec![(0, 1), (1, 2), (3, 1)])
+    }
+
+    #[test]
+    fn test_tokens_compound() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_compounds());
+        let result: Vec<usize> = recogniser
+            .tokens("seventeenine twenty-one")
+            .iter()
+            .map(|t| t.value)
+            .collect();
+        assert_eq!(result, vec![17, 9, 21])
+    }
+
+    #[test]
+    fn test_unicode_digits_mixed_script() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_unicode_digits());
+        assert_eq!(
+            recogniser.calibration_value("٣abcseven", &CalibrationPolicy::FirstLast),
+            Some(37)
+        );
+        assert_eq!(
+            recogniser.calibration_value("x१x２x", &CalibrationPolicy::FirstLast),
+            Some(12)
+        );
+        assert_eq!(
+            recogniser.calibration_value("۹", &CalibrationPolicy::FirstLast),
+            Some(99)
+        );
+    }
+
+    #[test]
+    fn test_unicode_digits_disabled() {
+        let recogniser = Recogniser::new(&Vocabulary::from_language(Language::English));
+        assert_eq!(
+            recogniser.calibration_value("٣abc", &CalibrationPolicy::FirstLast),
+            None
+        );
+    }
+
+    #[test]
+    fn test_roman_numerals_standalone() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_roman_numerals());
+        assert_eq!(
+            recogniser.calibration_value("chapter IV, part 2", &CalibrationPolicy::FirstLast),
+            Some(42)
+        );
+        assert_eq!(
+            recogniser.calibration_value("one (VIII)", &CalibrationPolicy::FirstLast),
+            Some(18)
+        );
+        assert_eq!(
+            recogniser.calibration_value("IX", &CalibrationPolicy::FirstLast),
+            Some(99)
+        );
+    }
+
+    #[test]
+    fn test_roman_numerals_rejected() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_roman_numerals());
+        assert_eq!(
+            recogniser.calibration_value("VIVID iv IIIa XIV Iris", &CalibrationPolicy::FirstLast),
+            None
+        );
+        assert_eq!(recogniser.tokens("I vIII V").len(), 2);
+    }
+
+    #[test]
+    fn test_calibration_value_policy() {
+        let recogniser =
+            Recogniser::new(&Vocabulary::from_language(Language::English).with_compounds());
+        let line = "8twenty-one3nine";
+        assert_eq!(
+            recogniser.calibration_value(line, &CalibrationPolicy::FirstLast),
+            Some(89)
+        );
+        assert_eq!(
+            recogniser.calibration_value(line, &CalibrationPolicy::Sum),
+            Some(23)
+        );
+        assert_eq!(
+            recogniser.calibration_value(line, &CalibrationPolicy::LargestPair),
+            Some(89)
+        );
+        assert_eq!(
+            recogniser.calibration_value(line, &CalibrationPolicy::Ends(2)),
+            Some(8239)
+        );
+    }
+}
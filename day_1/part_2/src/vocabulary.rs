@@ -0,0 +1,258 @@
+use clap::ValueEnum;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::path::Path;
+
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Language {
+    English,
+    French,
+    German,
+    Spanish,
+}
+
+static ENGLISH: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+static TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+static TENS: [&str; 8] = [
+    "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+static ROMAN: [&str; 9] = ["I", "II", "III", "IV", "V", "VI", "VII", "VIII", "IX"];
+// Zero of the Arabic-Indic, Extended Arabic-Indic, Devanagari and fullwidth digit blocks
+static DIGIT_BLOCKS: [u32; 4] = [0x0660, 0x06F0, 0x0966, 0xFF10];
+static FRENCH: [&str; 9] = [
+    "un", "deux", "trois", "quatre", "cinq", "six", "sept", "huit", "neuf",
+];
+static GERMAN: [&str; 9] = [
+    "eins", "zwei", "drei", "vier", "fünf", "sechs", "sieben", "acht", "neun",
+];
+static SPANISH: [&str; 9] = [
+    "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+];
+
+#[derive(Debug)]
+pub enum VocabularyError {
+    Io(std::io::Error),
+    Parse(String),
+    UnsupportedFormat(String),
+}
+
+impl Display for VocabularyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VocabularyError::Io(e) => write!(f, "could not read word list: {}", e),
+            VocabularyError::Parse(e) => write!(f, "could not parse word list: {}", e),
+            VocabularyError::UnsupportedFormat(path) => {
+                write!(f, "word list {} must be a .toml or .json file", path)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Vocabulary {
+    words: Vec<(String, usize)>,
+    roman: Vec<(String, usize)>,
+}
+
+impl Vocabulary {
+    pub fn from_language(language: Language) -> Self {
+        let words = match language {
+            Language::English => ENGLISH,
+            Language::French => FRENCH,
+            Language::German => GERMAN,
+            Language::Spanish => SPANISH,
+        };
+        Self {
+            words: words
+                .iter()
+                .zip(1..)
+                .map(|(word, value)| (word.to_string(), value))
+                .collect(),
+            roman: Vec::new(),
+        }
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, VocabularyError> {
+        let contents = fs::read_to_string(path).map_err(VocabularyError::Io)?;
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Self::from_toml(&contents),
+            Some("json") => Self::from_json(&contents),
+            _ => Err(VocabularyError::UnsupportedFormat(
+                path.display().to_string(),
+            )),
+        }
+    }
+
+    // Word lists are flat maps from word to value, e.g. `un = 1` or `{"un": 1}`
+    pub fn from_toml(contents: &str) -> Result<Self, VocabularyError> {
+        let words: BTreeMap<String, usize> =
+            toml::from_str(contents).map_err(|e| VocabularyError::Parse(e.to_string()))?;
+        Self::from_words(words)
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, VocabularyError> {
+        let words: BTreeMap<String, usize> =
+            serde_json::from_str(contents).map_err(|e| VocabularyError::Parse(e.to_string()))?;
+        Self::from_words(words)
+    }
+
+    // An empty word would match at every position of every line
+    fn from_words(words: BTreeMap<String, usize>) -> Result<Self, VocabularyError> {
+        if let Some(value) = words
+            .iter()
+            .find(|(word, _)| word.is_empty())
+            .map(|(_, v)| v)
+        {
+            return Err(VocabularyError::Parse(format!(
+                "empty word for value {}",
+                value
+            )));
+        }
+        let mut out: Vec<(String, usize)> = words
+            .into_iter()
+            .map(|(word, value)| (word.to_lowercase(), value))
+            .collect();
+        out.sort();
+        Ok(Self {
+            words: out,
+            roman: Vec::new(),
+        })
+    }
+
+    // Adds English "zero", the teens, the tens and hyphenated compounds such as "twenty-one"
+    pub fn with_compounds(mut self) -> Self {
+        self.words.push(("zero".to_string(), 0));
+        self.words.extend(
+            TEENS
+                .iter()
+                .zip(10..)
+                .map(|(word, value)| (word.to_string(), value)),
+        );
+        for (tens, tens_value) in TENS.iter().zip((20..).step_by(10)) {
+            self.words.push((tens.to_string(), tens_value));
+            self.words.extend(
+                ENGLISH
+                    .iter()
+                    .zip(1..)
+                    .map(|(unit, value)| (format!("{}-{}", tens, unit), tens_value + value)),
+            );
+        }
+        self
+    }
+
+    pub fn with_unicode_digits(mut self) -> Self {
+        for block in DIGIT_BLOCKS {
+            self.words.extend((0..10).map(|value| {
+                let digit = char::from_u32(block + value).expect("Digit blocks are valid chars");
+                (digit.to_string(), value as usize)
+            }));
+        }
+        self
+    }
+
+    // Roman numerals only count in upper case and when not joined to letters or digits
+    pub fn with_roman_numerals(mut self) -> Self {
+        self.roman = ROMAN
+            .iter()
+            .zip(1..)
+            .map(|(numeral, value)| (numeral.to_string(), value))
+            .collect();
+        self
+    }
+
+    pub fn words(&self) -> &[(String, usize)] {
+        &self.words
+    }
+
+    pub fn roman(&self) -> &[(String, usize)] {
+        &self.roman
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::vocabulary::{Language, Vocabulary, VocabularyError};
+
+    #[test]
+    fn test_english_values() {
+        let result = Vocabulary::from_language(Language::English);
+        assert_eq!(result.words()[0], ("one".to_string(), 1));
+        assert_eq!(result.words()[8], ("nine".to_string(), 9));
+    }
+
+    #[test]
+    fn test_german_values() {
+        let result = Vocabulary::from_language(Language::German);
+        assert_eq!(result.words()[4], ("fünf".to_string(), 5));
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let result = Vocabulary::from_toml("un = 1\ndeux = 2").unwrap();
+        assert_eq!(
+            result.words(),
+            &[("deux".to_string(), 2), ("un".to_string(), 1)]
+        )
+    }
+
+    #[test]
+    fn test_from_json() {
+        let result = Vocabulary::from_json(r#"{"Uno": 1, "dos": 2}"#).unwrap();
+        assert_eq!(
+            result.words(),
+            &[("dos".to_string(), 2), ("uno".to_string(), 1)]
+        )
+    }
+
+    #[test]
+    fn test_empty_word() {
+        let result = Vocabulary::from_json(r#"{"": 5, "cinq": 5}"#);
+        assert!(matches!(result, Err(VocabularyError::Parse(_))));
+    }
+
+    #[test]
+    fn test_multi_digit_value() {
+        let result = Vocabulary::from_json(r#"{"dix": 10}"#).unwrap();
+        assert_eq!(result.words(), &[("dix".to_string(), 10)])
+    }
+
+    #[test]
+    fn test_with_compounds() {
+        let result = Vocabulary::from_language(Language::English).with_compounds();
+        assert_eq!(result.words().len(), 9 + 1 + 10 + 8 * 10);
+        assert!(result.words().contains(&("zero".to_string(), 0)));
+        assert!(result.words().contains(&("seventeen".to_string(), 17)));
+        assert!(result.words().contains(&("forty".to_string(), 40)));
+        assert!(result.words().contains(&("ninety-nine".to_string(), 99)));
+    }
+
+    #[test]
+    fn test_with_unicode_digits() {
+        let result = Vocabulary::from_language(Language::English).with_unicode_digits();
+        assert!(result.words().contains(&("٣".to_string(), 3)));
+        assert!(result.words().contains(&("۷".to_string(), 7)));
+        assert!(result.words().contains(&("९".to_string(), 9)));
+        assert!(result.words().contains(&("０".to_string(), 0)));
+    }
+
+    #[test]
+    fn test_with_roman_numerals() {
+        let result = Vocabulary::from_language(Language::English).with_roman_numerals();
+        assert_eq!(result.roman()[3], ("IV".to_string(), 4));
+        assert_eq!(result.roman()[8], ("IX".to_string(), 9));
+    }
+}
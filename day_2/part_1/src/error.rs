@@ -0,0 +1,72 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+// Each span is the zero-based, end-exclusive range of columns holding the offending text
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum GameParseError {
+    MissingHeader(Range<usize>),
+    BadCount(Range<usize>),
+    UnknownColour(Range<usize>),
+    EmptyRound(Range<usize>),
+}
+
+impl GameParseError {
+    pub fn span(&self) -> &Range<usize> {
+        match self {
+            GameParseError::MissingHeader(span)
+            | GameParseError::BadCount(span)
+            | GameParseError::UnknownColour(span)
+            | GameParseError::EmptyRound(span) => span,
+        }
+    }
+
+    // The parser works in byte offsets, which only match columns on ASCII lines
+    pub fn in_columns(self, line: &str) -> Self {
+        let span = self.span();
+        let start = line[..span.start].chars().count();
+        let columns = start..start + line[span.clone()].chars().count();
+        match self {
+            GameParseError::MissingHeader(_) => GameParseError::MissingHeader(columns),
+            GameParseError::BadCount(_) => GameParseError::BadCount(columns),
+            GameParseError::UnknownColour(_) => GameParseError::UnknownColour(columns),
+            GameParseError::EmptyRound(_) => GameParseError::EmptyRound(columns),
+        }
+    }
+}
+
+impl Display for GameParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let problem = match self {
+            GameParseError::MissingHeader(_) => "missing \"Game N:\" header",
+            GameParseError::BadCount(_) => "bad count",
+            GameParseError::UnknownColour(_) => "unknown colour",
+            GameParseError::EmptyRound(_) => "empty round",
+        };
+        let span = self.span();
+        match span.len() {
+            0 | 1 => write!(f, "{} at column {}", problem, span.start + 1),
+            _ => write!(f, "{} at columns {}-{}", problem, span.start + 1, span.end),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::error::GameParseError;
+
+    #[test]
+    fn test_in_columns() {
+        let line = "Game 1: 2 grün, 3 rød";
+        let error = GameParseError::UnknownColour(line.find("rød").unwrap()..line.len());
+        assert_eq!(
+            error.in_columns(line),
+            GameParseError::UnknownColour(18..21)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        let error = GameParseError::BadCount(8..10);
+        assert_eq!(error.to_string(), "bad count at columns 9-10");
+    }
+}
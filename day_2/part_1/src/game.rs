@@ -1,63 +1,171 @@
-use std::str::FromStr;
-use strum::EnumString;
+use crate::error::GameParseError;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
 
+#[derive(Debug, Default, Eq, PartialEq, Clone)]
+pub struct Round {
+    pub counts: BTreeMap<String, usize>,
+}
+
+impl Round {
+    pub fn new(counts: BTreeMap<String, usize>) -> Self {
+        Round { counts }
+    }
+
+    pub fn count(&self, colour: &str) -> usize {
+        self.counts.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+}
+
+impl<const N: usize> From<[(&str, usize); N]> for Round {
+    fn from(counts: [(&str, usize); N]) -> Self {
+        Round::new(counts.iter().map(|(c, n)| (c.to_string(), *n)).collect())
+    }
+}
+
+impl Display for Round {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let draws: Vec<String> = self
+            .counts
+            .iter()
+            .map(|(colour, count)| format!("{} {}", count, colour))
+            .collect();
+        write!(f, "{}", draws.join(", "))
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Violation {
+    pub round: usize,
+    pub colour: String,
+    pub count: usize,
+    pub limit: usize,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "round {} drew {} {} (limit {})",
+            self.round + 1,
+            self.count,
+            self.colour,
+            self.limit
+        )
+    }
+}
+
+#[derive(Debug, Eq, PartialEq, Clone)]
 pub struct Game {
     pub id: usize,
-    pub known_red: usize,
-    pub known_green: usize,
-    pub known_blue: usize,
+    pub rounds: Vec<Round>,
 }
 
-#[derive(EnumString, Debug, Eq, PartialEq)]
-enum Colour {
-    RED,
-    GREEN,
-    BLUE,
+// Written the way the puzzle input is, so `game_from_line` parses it back to an equal
+// `Game`. Draws within a round come out in colour order, not the order they were read in
+impl Display for Game {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rounds: Vec<String> = self.rounds.iter().map(Round::to_string).collect();
+        write!(f, "Game {}: {}", self.id, rounds.join("; "))
+    }
 }
 
 impl Game {
-    pub fn new(id: usize, red: usize, green: usize, blue: usize) -> Self {
-        Game {
-            id,
-            known_red: red,
-            known_green: green,
-            known_blue: blue,
+    pub fn new(id: usize, rounds: Vec<Round>) -> Self {
+        Game { id, rounds }
+    }
+
+    pub fn game_from_line(line: String) -> Result<Self, GameParseError> {
+        Self::parse(&line).map_err(|e| e.in_columns(&line))
+    }
+
+    fn parse(line: &str) -> Result<Self, GameParseError> {
+        let colon = line
+            .find(':')
+            .ok_or(GameParseError::MissingHeader(0..line.len()))?;
+        let id = line[..colon]
+            .trim()
+            .strip_prefix("Game")
+            .and_then(|id| id.trim().parse::<usize>().ok())
+            .ok_or(GameParseError::MissingHeader(0..colon))?;
+        let mut rounds = Vec::new();
+        let mut start = colon + 1;
+        for round in line[start..].split(';') {
+            rounds.push(Self::round_from_str(round, start)?);
+            start += round.len() + 1;
+        }
+        Ok(Game::new(id, rounds))
+    }
+
+    // `offset` is where `round` starts in its line, so errors point at the whole line
+    fn round_from_str(round: &str, offset: usize) -> Result<Round, GameParseError> {
+        if round.trim().is_empty() {
+            return Err(GameParseError::EmptyRound(offset..offset + round.len()));
         }
+        let mut out = Round::default();
+        for (size, colour) in Self::get_numbers_and_colours(round, offset)? {
+            *out.counts.entry(colour).or_insert(0) += size;
+        }
+        Ok(out)
     }
 
-    pub fn game_from_line(line: String) -> Self {
-        let first_split: Vec<&str> = line.split(':').map(|s| s.trim()).collect();
-        let id = first_split
-            .first()
-            .unwrap()
-            .split(' ')
-            .last()
-            .unwrap()
-            .parse::<usize>()
-            .unwrap();
-        let colours = Self::get_numbers_and_colours(first_split.last().unwrap());
-        let mut largest_red = 0;
-        let mut largest_green = 0;
-        let mut largest_blue = 0;
-
-        for (size, colour) in colours {
-            match colour {
-                Colour::RED => largest_red = largest_red.max(size),
-                Colour::GREEN => largest_green = largest_green.max(size),
-                Colour::BLUE => largest_blue = largest_blue.max(size),
+    fn get_numbers_and_colours(
+        match_string: &str,
+        offset: usize,
+    ) -> Result<Vec<(usize, String)>, GameParseError> {
+        let mut out: Vec<(usize, String)> = vec![];
+        let mut start = offset;
+        for draw in match_string.split(',') {
+            let text = draw.trim();
+            let text_start = start + draw.len() - draw.trim_start().len();
+            let (count, colour) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+            let number_part = count
+                .parse::<usize>()
+                .map_err(|_| GameParseError::BadCount(text_start..text_start + count.len()))?;
+            let colour = colour.trim_start();
+            let colour_start = text_start + text.len() - colour.len();
+            if colour.is_empty() || !colour.chars().all(char::is_alphabetic) {
+                return Err(GameParseError::UnknownColour(
+                    colour_start..colour_start + colour.len(),
+                ));
             }
+            out.push((number_part, colour.to_lowercase()));
+            start += draw.len() + 1;
+        }
+        Ok(out)
+    }
+
+    pub fn minimum_bag(&self) -> Round {
+        let mut bag = Round::default();
+        for (colour, count) in self.rounds.iter().flat_map(|r| &r.counts) {
+            let largest = bag.counts.entry(colour.clone()).or_insert(0);
+            *largest = (*largest).max(*count);
         }
-        Game::new(id, largest_red, largest_green, largest_blue)
+        bag
     }
 
-    fn get_numbers_and_colours(match_string: &str) -> Vec<(usize, Colour)> {
-        let pattern = regex::Regex::new(r"\d+ (blue|green|red)").unwrap();
-        let mut out: Vec<(usize, Colour)> = vec![];
-        for m in pattern.find_iter(match_string.trim()) {
-            let parts: Vec<&str> = m.as_str().split(' ').collect();
-            let number_part = parts[0].parse::<usize>().unwrap();
-            let colour = Colour::from_str(parts[1].to_uppercase().as_str()).unwrap();
-            out.push((number_part, colour));
+    pub fn total_cubes(&self) -> usize {
+        self.rounds.iter().map(Round::total).sum()
+    }
+
+    // Every draw of a colour beyond the limit, in round order
+    pub fn violations(&self, limit: &Round) -> Vec<Violation> {
+        let mut out = Vec::new();
+        for (round, draws) in self.rounds.iter().enumerate() {
+            for (colour, count) in &draws.counts {
+                if *count > limit.count(colour) {
+                    out.push(Violation {
+                        round,
+                        colour: colour.clone(),
+                        count: *count,
+                        limit: limit.count(colour),
+                    });
+                }
+            }
         }
         out
     }
@@ -65,28 +173,125 @@ impl Game {
 
 #[cfg(test)]
 mod test {
-    use crate::game::Colour;
+    use crate::error::GameParseError;
+    use crate::game::Round;
 
     #[test]
     fn test_number_colour_parse_1() {
-        let result = super::Game::get_numbers_and_colours("5 blue");
-        assert_eq!(result, vec![(5, Colour::BLUE)])
+        let result = super::Game::get_numbers_and_colours("5 blue", 0);
+        assert_eq!(result, Ok(vec![(5, "blue".to_string())]))
     }
 
     #[test]
     fn test_number_colour_parse_2() {
-        let result = super::Game::get_numbers_and_colours("5 blue, 3 green");
-        assert_eq!(result, vec![(5, Colour::BLUE), (3, Colour::GREEN)])
+        let result = super::Game::get_numbers_and_colours("5 blue, 3 green", 0);
+        assert_eq!(
+            result,
+            Ok(vec![(5, "blue".to_string()), (3, "green".to_string())])
+        )
     }
 
     #[test]
     fn test_simple_1() {
         let result = super::Game::game_from_line(
             "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string(),
-        );
+        )
+        .unwrap();
         assert_eq!(result.id, 1);
-        assert_eq!(result.known_red, 4);
-        assert_eq!(result.known_green, 2);
-        assert_eq!(result.known_blue, 6);
+        assert_eq!(result.minimum_bag().count("red"), 4);
+        assert_eq!(result.minimum_bag().count("green"), 2);
+        assert_eq!(result.minimum_bag().count("blue"), 6);
+    }
+
+    #[test]
+    fn test_rounds_kept_in_order() {
+        let result = super::Game::game_from_line(
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string(),
+        )
+        .unwrap();
+        assert_eq!(
+            result.rounds,
+            vec![
+                Round::from([("red", 4), ("blue", 3)]),
+                Round::from([("red", 1), ("green", 2), ("blue", 6)]),
+                Round::from([("green", 2)])
+            ]
+        );
+        assert_eq!(result.total_cubes(), 18);
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let line = "Game 12: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
+        let result = super::Game::game_from_line(line.to_string()).unwrap();
+        assert_eq!(
+            result.to_string(),
+            "Game 12: 3 blue, 4 red; 6 blue, 2 green, 1 red; 2 green"
+        );
+        assert_eq!(
+            super::Game::game_from_line(result.to_string()).unwrap(),
+            result
+        );
+    }
+
+    #[test]
+    fn test_violations() {
+        let result = super::Game::game_from_line(
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red".to_string(),
+        )
+        .unwrap();
+        let limit = Round::from([("red", 12), ("green", 12), ("blue", 14)]);
+        assert_eq!(
+            result
+                .violations(&limit)
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<String>>(),
+            vec![
+                "round 1 drew 20 red (limit 12)",
+                "round 2 drew 13 green (limit 12)"
+            ]
+        );
+        let limit = Round::from([("red", 20), ("green", 13), ("blue", 14)]);
+        assert!(result.violations(&limit).is_empty());
+    }
+
+    #[test]
+    fn test_other_colours() {
+        let result =
+            super::Game::game_from_line("Game 7: 2 yellow, 1 purple; 4 Yellow".to_string())
+                .unwrap();
+        assert_eq!(
+            result.minimum_bag(),
+            Round::from([("yellow", 4), ("purple", 1)])
+        );
+        // A colour missing from the limit is not in the bag at all
+        let limit = Round::from([("yellow", 4)]);
+        assert_eq!(result.violations(&limit)[0].colour, "purple");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |line: &str| super::Game::game_from_line(line.to_string()).err();
+        assert_eq!(
+            parse("3 blue, 4 red"),
+            Some(GameParseError::MissingHeader(0..13))
+        );
+        assert_eq!(
+            parse("Gme 1: 3 blue"),
+            Some(GameParseError::MissingHeader(0..5))
+        );
+        assert_eq!(
+            parse("Game 1: 3 blue, x red"),
+            Some(GameParseError::BadCount(16..17))
+        );
+        assert_eq!(
+            parse("Game 1: 3 blue, 4 re-d"),
+            Some(GameParseError::UnknownColour(18..22))
+        );
+        assert_eq!(
+            parse("Game 1: 3 blue;; 2 red"),
+            Some(GameParseError::EmptyRound(15..15))
+        );
     }
 }
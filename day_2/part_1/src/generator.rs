@@ -0,0 +1,175 @@
+use crate::game::{Game, Round};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+// Each round draws a random number of cubes from the bag without putting any back,
+// then the whole round goes back in before the next one. Cubes are only ever counted,
+// never held one by one, so a bag can be as large as its total fits in a usize
+pub struct Generator {
+    counts: Vec<(String, usize)>,
+    total: usize,
+    max_rounds: usize,
+    rng: StdRng,
+}
+
+impl Generator {
+    // An empty bag would only give empty rounds, which no game line can hold
+    pub fn new(bag: &Round, max_rounds: usize, seed: u64) -> Result<Self, String> {
+        let counts: Vec<(String, usize)> = bag
+            .counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(colour, count)| (colour.clone(), *count))
+            .collect();
+        let total = counts
+            .iter()
+            .try_fold(0_usize, |total, (_, count)| total.checked_add(*count))
+            .ok_or("the bag holds more cubes than can be counted")?;
+        if total == 0 {
+            return Err("the bag needs at least one cube to draw from".to_string());
+        }
+        Ok(Generator {
+            counts,
+            total,
+            max_rounds: max_rounds.max(1),
+            rng: StdRng::seed_from_u64(seed),
+        })
+    }
+
+    pub fn game(&mut self, id: usize) -> Game {
+        let rounds = self.rng.gen_range(1..=self.max_rounds);
+        Game::new(id, (0..rounds).map(|_| self.round()).collect())
+    }
+
+    // How many of the drawn cubes are of each colour follows a hypergeometric draw from
+    // the cubes not yet considered, one colour at a time; the last colour takes the rest
+    pub fn round(&mut self) -> Round {
+        let mut out = Round::default();
+        let mut left = self.rng.gen_range(1..=self.total);
+        let mut remaining = self.total;
+        for (colour, count) in &self.counts {
+            if left == 0 {
+                break;
+            }
+            let drawn = hypergeometric(&mut self.rng, remaining, *count, left);
+            if drawn > 0 {
+                out.counts.insert(colour.clone(), drawn);
+            }
+            remaining -= count;
+            left -= drawn;
+        }
+        out
+    }
+}
+
+// Weights this far below the mode's no longer register against a sample in [0, 1)
+const NEGLIGIBLE: f64 = 1e-20;
+
+// How many of `marked` cubes turn up when `drawn` are taken from `total` without putting
+// any back. Weights relative to the most likely count are walked outward from it until
+// they become negligible, so the cost grows with the spread rather than with the bag
+fn hypergeometric(rng: &mut StdRng, total: usize, marked: usize, drawn: usize) -> usize {
+    let low = drawn.saturating_sub(total - marked);
+    let high = drawn.min(marked);
+    if low == high {
+        return low;
+    }
+    let (n, k, d) = (total as f64, marked as f64, drawn as f64);
+    let mode = (((d + 1.0) * (k + 1.0) / (n + 2.0)) as usize).clamp(low, high);
+    // The probability of x + 1 marked cubes over that of x
+    let ratio = move |x: usize| {
+        let x = x as f64;
+        (k - x) * (d - x) / ((x + 1.0) * (n - k - d + x + 1.0))
+    };
+    let side = move |upward: bool| {
+        std::iter::successors(Some((mode, 1.0)), move |&(x, weight): &(usize, f64)| {
+            let next = match upward {
+                true => (x < high).then(|| (x + 1, weight * ratio(x))),
+                false => (x > low).then(|| (x - 1, weight / ratio(x - 1))),
+            };
+            next.filter(|(_, weight)| *weight > NEGLIGIBLE)
+        })
+        .skip(1)
+    };
+    let sum = 1.0 + side(true).chain(side(false)).map(|(_, w)| w).sum::<f64>();
+    let mut target = rng.gen::<f64>() * sum - 1.0;
+    for (x, weight) in side(true).chain(side(false)) {
+        if target < 0.0 {
+            break;
+        }
+        if target < weight {
+            return x;
+        }
+        target -= weight;
+    }
+    mode
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::{Game, Round};
+    use crate::generator::{hypergeometric, Generator};
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_same_seed_same_games() {
+        let bag = Round::from([("red", 12), ("green", 13), ("blue", 14)]);
+        let mut first = Generator::new(&bag, 6, 7).unwrap();
+        let mut second = Generator::new(&bag, 6, 7).unwrap();
+        for id in 1..=20 {
+            assert_eq!(first.game(id), second.game(id));
+        }
+    }
+
+    #[test]
+    fn test_generated_games_fit_the_bag() {
+        let bag = Round::from([("red", 3), ("yellow", 2)]);
+        let mut generator = Generator::new(&bag, 4, 1).unwrap();
+        for id in 1..=100 {
+            let game = generator.game(id);
+            assert!((1..=4).contains(&game.rounds.len()));
+            assert!(game.violations(&bag).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_generated_games_round_trip() {
+        let bag = Round::from([("red", 12), ("green", 13), ("blue", 14), ("purple", 2)]);
+        let mut generator = Generator::new(&bag, 8, 2023).unwrap();
+        for id in 1..=200 {
+            let game = generator.game(id);
+            assert_eq!(Game::game_from_line(game.to_string()).unwrap(), game);
+        }
+    }
+
+    #[test]
+    fn test_huge_bag() {
+        let bag = Round::from([("red", 1_000_000_000_000), ("blue", 3)]);
+        let mut generator = Generator::new(&bag, 4, 5).unwrap();
+        for id in 1..=20 {
+            let game = generator.game(id);
+            assert!(game.violations(&bag).is_empty());
+            assert_eq!(Game::game_from_line(game.to_string()).unwrap(), game);
+        }
+    }
+
+    #[test]
+    fn test_hypergeometric_mean() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let samples: Vec<usize> = (0..2000)
+            .map(|_| hypergeometric(&mut rng, 50, 20, 10))
+            .collect();
+        assert!(samples.iter().all(|x| *x <= 10));
+        let mean = samples.iter().sum::<usize>() as f64 / samples.len() as f64;
+        assert!((mean - 4.0).abs() < 0.1);
+        assert_eq!(hypergeometric(&mut rng, 10, 10, 7), 7);
+        assert_eq!(hypergeometric(&mut rng, 10, 0, 7), 0);
+    }
+
+    #[test]
+    fn test_empty_bag() {
+        let bag = Round::from([("red", 0)]);
+        assert!(Generator::new(&bag, 6, 0).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+use crate::game::Round;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+#[derive(Deserialize)]
+struct LimitsFile {
+    limits: BTreeMap<String, usize>,
+}
+
+pub fn default_limits() -> Round {
+    Round::from([("red", 12), ("green", 13), ("blue", 14)])
+}
+
+// Parses a single `--limit colour=count` flag
+pub fn parse_limit(limit: &str) -> Result<(String, usize), String> {
+    let (colour, count) = limit
+        .split_once('=')
+        .ok_or(format!("expected COLOUR=COUNT, found {:?}", limit))?;
+    let count = count
+        .trim()
+        .parse::<usize>()
+        .map_err(|e| format!("bad count for {}: {}", colour, e))?;
+    Ok((colour.trim().to_lowercase(), count))
+}
+
+// A config file holds a `[limits]` table such as `red = 12`
+pub fn limits_from_toml(contents: &str) -> Result<Round, String> {
+    let file: LimitsFile = toml::from_str(contents).map_err(|e| e.to_string())?;
+    Ok(Round::new(
+        file.limits
+            .into_iter()
+            .map(|(colour, count)| (colour.to_lowercase(), count))
+            .collect(),
+    ))
+}
+
+// Flags override the config file, and the puzzle limits apply when neither is given
+pub fn load_limits(config: Option<&Path>, flags: &[(String, usize)]) -> Result<Round, String> {
+    let mut limits = match config {
+        Some(path) => {
+            let contents = fs::read_to_string(path)
+                .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+            limits_from_toml(&contents)?
+        }
+        None if flags.is_empty() => default_limits(),
+        None => Round::default(),
+    };
+    limits.counts.extend(flags.iter().cloned());
+    Ok(limits)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::Round;
+    use crate::limits::{limits_from_toml, load_limits, parse_limit};
+
+    #[test]
+    fn test_parse_limit() {
+        assert_eq!(parse_limit("Red=12"), Ok(("red".to_string(), 12)));
+        assert!(parse_limit("red").is_err());
+        assert!(parse_limit("red=many").is_err());
+    }
+
+    #[test]
+    fn test_limits_from_toml() {
+        let result = limits_from_toml("[limits]\nyellow = 3\npurple = 4\n");
+        assert_eq!(result, Ok(Round::from([("yellow", 3), ("purple", 4)])));
+    }
+
+    #[test]
+    fn test_load_limits_flags_only() {
+        let result = load_limits(None, &[("yellow".to_string(), 2)]);
+        assert_eq!(result, Ok(Round::from([("yellow", 2)])));
+    }
+
+    #[test]
+    fn test_load_limits_default() {
+        let result = load_limits(None, &[]).unwrap();
+        assert_eq!(result.count("blue"), 14);
+    }
+}
@@ -1,20 +1,173 @@
+mod error;
 mod game;
+mod generator;
+mod limits;
+mod sensitivity;
 
-use crate::game::Game;
-use std::io::stdin;
+use crate::game::{Game, Round};
+use crate::generator::Generator;
+use crate::limits::{default_limits, load_limits, parse_limit};
+use crate::sensitivity::{admit, colours, possible_total, sweep};
+use clap::{Parser, Subcommand};
+use std::io::{stdin, BufRead, ErrorKind};
+use std::path::PathBuf;
+use std::process;
+
+#[derive(Parser)]
+struct Args {
+    /// Most cubes of one colour in the bag, e.g. red=12; may be repeated
+    #[arg(long = "limit", value_parser = parse_limit)]
+    limits: Vec<(String, usize)>,
+    /// TOML file with a [limits] table; --limit flags override it
+    #[arg(long)]
+    config: Option<PathBuf>,
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print synthetic games drawn from a bag instead of reading any
+    Generate {
+        /// Cubes of one colour in the bag, e.g. red=12; may be repeated
+        #[arg(long = "bag", value_parser = parse_limit)]
+        bag: Vec<(String, usize)>,
+        #[arg(long, default_value_t = 100)]
+        games: usize,
+        #[arg(long, default_value_t = 6)]
+        max_rounds: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Show how the total of possible games changes as each colour's limit moves
+    Sensitivity,
+    /// Find the smallest raise of the limits that makes the given games possible
+    Admit {
+        #[arg(long = "game", required = true)]
+        games: Vec<usize>,
+    },
+}
 
 fn main() {
-    let lines = stdin().lines();
-    let games = lines.map(|l| Game::game_from_line(l.unwrap()));
-    let red_limit = 12;
-    let green_limit = 13;
-    let blue_limit = 14;
+    let args = Args::parse();
+    if let Some(Command::Generate {
+        bag,
+        games,
+        max_rounds,
+        seed,
+    }) = args.command
+    {
+        let bag = match bag.is_empty() {
+            true => default_limits(),
+            false => Round::new(bag.into_iter().collect()),
+        };
+        let mut generator = Generator::new(&bag, max_rounds, seed).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            process::exit(1)
+        });
+        for id in 1..=games {
+            println!("{}", generator.game(id));
+        }
+        return;
+    }
+    let limit = load_limits(args.config.as_deref(), &args.limits).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    let games = read_games(stdin().lock());
+    match args.command {
+        Some(Command::Sensitivity) => print_sensitivity(&games, &limit),
+        Some(Command::Admit { games: targets }) => match admit(&games, &limit, &targets) {
+            Ok(adjustment) => {
+                for (colour, old, new) in &adjustment.changes {
+                    println!("{}: {} -> {}", colour, old, new);
+                }
+                let admitted: Vec<String> =
+                    adjustment.admitted.iter().map(usize::to_string).collect();
+                println!("Newly possible games: {}", admitted.join(", "));
+                println!("{}", adjustment.total);
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(1)
+            }
+        },
+        _ => print_report(&games, &limit),
+    }
+}
+
+fn print_sensitivity(games: &[Game], limit: &Round) {
+    println!("{} with {}", possible_total(games, limit), limit);
+    for colour in colours(games, limit) {
+        let steps: Vec<String> = sweep(games, limit, &colour)
+            .iter()
+            .map(|(count, total)| format!("{} from {}", total, count))
+            .collect();
+        println!(
+            "{} (limit {}): {}",
+            colour,
+            limit.count(&colour),
+            steps.join(", ")
+        );
+    }
+}
 
+fn print_report(games: &[Game], limit: &Round) {
     let mut total = 0;
-    for game in games.filter(|g| {
-        g.known_red <= red_limit && g.known_green <= green_limit && g.known_blue <= blue_limit
-    }) {
-        total += game.id;
-        println!("{}", total);
+    let mut possible = Vec::new();
+    for game in games {
+        let violations = game.violations(limit);
+        if violations.is_empty() {
+            total += game.id;
+            possible.push(game.id.to_string());
+            println!(
+                "Game {}: possible with {} ({} cubes over {} rounds)",
+                game.id,
+                game.minimum_bag(),
+                game.total_cubes(),
+                game.rounds.len()
+            );
+        } else {
+            let reasons: Vec<String> = violations.iter().map(|v| v.to_string()).collect();
+            println!("Game {}: impossible, {}", game.id, reasons.join("; "));
+        }
+    }
+    println!("Possible games: {}", possible.join(", "));
+    println!("{}", total);
+}
+
+// A malformed line is reported and skipped rather than ending the run
+fn read_games(input: impl BufRead) -> Vec<Game> {
+    let mut games = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) if e.kind() == ErrorKind::InvalidData => {
+                eprintln!("line {}: invalid UTF-8, skipped", index + 1);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("line {}: {}", index + 1, e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match Game::game_from_line(line) {
+            Ok(game) => games.push(game),
+            Err(e) => eprintln!("line {}: {}, skipped", index + 1, e),
+        }
+    }
+    games
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_read_games_skips_invalid_utf8_and_blank_lines() {
+        let input: &[u8] = b"Game 1: 3 blue\nGame 2: 3 bl\xffue\n\nGame 3: 1 red\n \n";
+        let ids: Vec<usize> = super::read_games(input).iter().map(|g| g.id).collect();
+        assert_eq!(ids, vec![1, 3]);
     }
 }
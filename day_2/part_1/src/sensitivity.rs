@@ -0,0 +1,135 @@
+use crate::game::{Game, Round};
+use std::collections::BTreeSet;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Adjustment {
+    pub limit: Round,
+    // Colour, old limit and new limit for every colour that had to be raised
+    pub changes: Vec<(String, usize, usize)>,
+    pub admitted: Vec<usize>,
+    pub total: usize,
+}
+
+pub fn possible_total(games: &[Game], limit: &Round) -> usize {
+    games
+        .iter()
+        .filter(|g| g.violations(limit).is_empty())
+        .map(|g| g.id)
+        .sum()
+}
+
+pub fn colours(games: &[Game], limit: &Round) -> BTreeSet<String> {
+    games
+        .iter()
+        .flat_map(|g| g.minimum_bag().counts.into_keys())
+        .chain(limit.counts.keys().cloned())
+        .collect()
+}
+
+// The possible-games total as one colour's limit moves with the others held fixed.
+// Each entry is the lowest limit at which the total takes a new value
+pub fn sweep(games: &[Game], limit: &Round, colour: &str) -> Vec<(usize, usize)> {
+    let mut needed: Vec<(usize, usize)> = games
+        .iter()
+        .map(|g| (g.minimum_bag(), g.id))
+        .filter(|(bag, _)| {
+            bag.counts
+                .iter()
+                .all(|(c, count)| c == colour || *count <= limit.count(c))
+        })
+        .map(|(bag, id)| (bag.count(colour), id))
+        .collect();
+    needed.sort();
+
+    let mut out = vec![(0, 0)];
+    for (count, id) in needed {
+        let (last_count, last_total) = out[out.len() - 1];
+        if count == last_count {
+            out.last_mut().unwrap().1 += id;
+        } else {
+            out.push((count, last_total + id));
+        }
+    }
+    out.dedup_by_key(|(_, total)| *total);
+    out
+}
+
+// Limits only ever need raising to admit a game, so the smallest change raises each
+// colour to the largest draw of it among the target games and leaves the rest alone
+pub fn admit(games: &[Game], limit: &Round, targets: &[usize]) -> Result<Adjustment, String> {
+    let mut new_limit = limit.clone();
+    for id in targets {
+        let game = games
+            .iter()
+            .find(|g| g.id == *id)
+            .ok_or(format!("no game with ID {}", id))?;
+        for (colour, count) in game.minimum_bag().counts {
+            let entry = new_limit.counts.entry(colour).or_insert(0);
+            *entry = (*entry).max(count);
+        }
+    }
+    let changes = new_limit
+        .counts
+        .iter()
+        .filter(|(colour, count)| **count != limit.count(colour))
+        .map(|(colour, count)| (colour.clone(), limit.count(colour), *count))
+        .collect();
+    let admitted = games
+        .iter()
+        .filter(|g| g.violations(&new_limit).is_empty() && !g.violations(limit).is_empty())
+        .map(|g| g.id)
+        .collect();
+    Ok(Adjustment {
+        total: possible_total(games, &new_limit),
+        limit: new_limit,
+        changes,
+        admitted,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::{Game, Round};
+    use crate::sensitivity::{admit, sweep};
+
+    fn example() -> Vec<Game> {
+        [
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green",
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue",
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red",
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red",
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green",
+        ]
+        .iter()
+        .map(|l| Game::game_from_line(l.to_string()).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_sweep_red() {
+        let limit = Round::from([("red", 12), ("green", 13), ("blue", 14)]);
+        // Game 4 also needs more blue, so no red limit admits it
+        assert_eq!(
+            sweep(&example(), &limit, "red"),
+            vec![(0, 0), (1, 2), (4, 3), (6, 8), (20, 11)]
+        );
+    }
+
+    #[test]
+    fn test_admit() {
+        let limit = Round::from([("red", 12), ("green", 13), ("blue", 14)]);
+        let result = admit(&example(), &limit, &[4]).unwrap();
+        assert_eq!(
+            result.changes,
+            vec![("blue".to_string(), 14, 15), ("red".to_string(), 12, 14)]
+        );
+        assert_eq!(result.admitted, vec![4]);
+        assert_eq!(result.total, 12);
+    }
+
+    #[test]
+    fn test_admit_unknown_game() {
+        let limit = Round::from([("red", 12)]);
+        assert!(admit(&example(), &limit, &[9]).is_err());
+    }
+}
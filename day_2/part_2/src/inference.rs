@@ -0,0 +1,249 @@
+use crate::game::{Game, Round};
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum Sampling {
+    /// Each cube goes back in the bag before the next is drawn
+    WithReplacement,
+    /// A round's cubes stay out until the round is over
+    WithoutReplacement,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub bag: Round,
+    pub log_likelihood: f64,
+    // Bag size and its posterior probability, in increasing size
+    pub posterior: Vec<(usize, f64)>,
+}
+
+impl Estimate {
+    pub fn most_likely_size(&self) -> Option<(usize, f64)> {
+        self.posterior
+            .iter()
+            .copied()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+    }
+
+    // With no known total the largest size considered is only a cap. A mode sitting on it
+    // says more about the cap than the game, which does not rule out larger bags
+    pub fn mode_at_cap(&self) -> bool {
+        self.posterior.len() > 1
+            && self.most_likely_size().map(|m| m.0) == self.posterior.last().map(|p| p.0)
+    }
+}
+
+// Every bag holding at least one cube of each colour seen in the game is equally likely
+// a priori, as is every size up to the largest considered
+pub struct Estimator {
+    sampling: Sampling,
+    total: Option<usize>,
+    max_size: usize,
+}
+
+impl Estimator {
+    pub fn new(sampling: Sampling, total: Option<usize>, max_size: usize) -> Self {
+        Estimator {
+            sampling,
+            total,
+            max_size,
+        }
+    }
+
+    pub fn estimate(&self, game: &Game) -> Option<Estimate> {
+        let colours: Vec<String> = game.colours().into_iter().collect();
+        if colours.is_empty() {
+            return None;
+        }
+        let minimum_bag = game.minimum_bag();
+        let lowest: Vec<usize> = colours
+            .iter()
+            .map(|colour| match self.sampling {
+                Sampling::WithReplacement => 1,
+                Sampling::WithoutReplacement => minimum_bag.count(colour).max(1),
+            })
+            .collect();
+        let sizes = match self.total {
+            Some(total) => total..=total,
+            None => lowest.iter().sum()..=self.max_size,
+        };
+        let draws: Vec<Vec<usize>> = game
+            .rounds
+            .iter()
+            .map(|round| colours.iter().map(|colour| round.count(colour)).collect())
+            .collect();
+        // With replacement a round may draw more cubes than the bag holds
+        let largest_round = draws.iter().map(|r| r.iter().sum()).max().unwrap_or(0);
+        let ln_factorials = ln_factorials(largest_round.max(*sizes.end()));
+
+        let mut best: Option<(f64, Vec<usize>)> = None;
+        let mut evidence = Vec::new();
+        for size in sizes {
+            let mut log_likelihoods = Vec::new();
+            for_each_composition(&lowest, size, &mut |bag| {
+                let log_likelihood = self.log_likelihood(bag, &draws, &ln_factorials);
+                // Scaling a bag up keeps the same proportions, so ties go to the smaller bag
+                if best.as_ref().is_none_or(|(b, _)| log_likelihood > b + 1e-9) {
+                    best = Some((log_likelihood, bag.to_vec()));
+                }
+                log_likelihoods.push(log_likelihood);
+            });
+            if !log_likelihoods.is_empty() {
+                let bags = ln_choose(&ln_factorials, size - 1, colours.len() - 1);
+                evidence.push((size, log_sum_exp(&log_likelihoods) - bags));
+            }
+        }
+
+        let (log_likelihood, bag) = best?;
+        let normaliser = log_sum_exp(&evidence.iter().map(|e| e.1).collect::<Vec<f64>>());
+        Some(Estimate {
+            bag: Round::new(colours.into_iter().zip(bag).collect()),
+            log_likelihood,
+            posterior: evidence
+                .into_iter()
+                .map(|(size, e)| (size, (e - normaliser).exp()))
+                .collect(),
+        })
+    }
+
+    fn log_likelihood(&self, bag: &[usize], draws: &[Vec<usize>], ln_factorials: &[f64]) -> f64 {
+        let size: usize = bag.iter().sum();
+        draws
+            .iter()
+            .map(|round| {
+                let drawn: usize = round.iter().sum();
+                match self.sampling {
+                    Sampling::WithReplacement => {
+                        ln_factorials[drawn]
+                            + bag
+                                .iter()
+                                .zip(round)
+                                .map(|(n, x)| {
+                                    *x as f64 * (*n as f64 / size as f64).ln() - ln_factorials[*x]
+                                })
+                                .sum::<f64>()
+                    }
+                    Sampling::WithoutReplacement => {
+                        bag.iter()
+                            .zip(round)
+                            .map(|(n, x)| ln_choose(ln_factorials, *n, *x))
+                            .sum::<f64>()
+                            - ln_choose(ln_factorials, size, drawn)
+                    }
+                }
+            })
+            .sum()
+    }
+}
+
+fn ln_factorials(largest: usize) -> Vec<f64> {
+    let mut out = vec![0.0; largest + 1];
+    for n in 1..=largest {
+        out[n] = out[n - 1] + (n as f64).ln();
+    }
+    out
+}
+
+fn ln_choose(ln_factorials: &[f64], n: usize, k: usize) -> f64 {
+    ln_factorials[n] - ln_factorials[k] - ln_factorials[n - k]
+}
+
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let largest = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if largest == f64::NEG_INFINITY {
+        return largest;
+    }
+    largest + values.iter().map(|v| (v - largest).exp()).sum::<f64>().ln()
+}
+
+// Calls `visit` with every bag of exactly `size` cubes holding at least `lowest` of each colour
+fn for_each_composition(lowest: &[usize], size: usize, visit: &mut dyn FnMut(&[usize])) {
+    fn fill(bag: &mut Vec<usize>, lowest: &[usize], left: usize, visit: &mut dyn FnMut(&[usize])) {
+        match lowest.len() - bag.len() {
+            0 => {}
+            1 => {
+                bag.push(lowest[bag.len()] + left);
+                visit(bag);
+                bag.pop();
+            }
+            _ => {
+                for extra in 0..=left {
+                    bag.push(lowest[bag.len()] + extra);
+                    fill(bag, lowest, left - extra, visit);
+                    bag.pop();
+                }
+            }
+        }
+    }
+    let floor: usize = lowest.iter().sum();
+    if size >= floor {
+        fill(&mut Vec::new(), lowest, size - floor, visit);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::game::{Game, Round};
+    use crate::inference::{Estimator, Sampling};
+
+    #[test]
+    fn test_known_total_is_minimum_bag() {
+        let game =
+            Game::game_from_line("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".into())
+                .unwrap();
+        let result = Estimator::new(Sampling::WithoutReplacement, Some(12), 100)
+            .estimate(&game)
+            .unwrap();
+        assert_eq!(result.bag, game.minimum_bag());
+        assert_eq!(result.posterior, vec![(12, 1.0)]);
+    }
+
+    #[test]
+    fn test_with_replacement_keeps_proportions() {
+        let game = Game::game_from_line("Game 2: 2 red, 1 blue; 4 red, 2 blue".into()).unwrap();
+        let result = Estimator::new(Sampling::WithReplacement, None, 9)
+            .estimate(&game)
+            .unwrap();
+        assert_eq!(result.bag, Round::from([("red", 2), ("blue", 1)]));
+    }
+
+    #[test]
+    fn test_posterior_covers_sizes() {
+        let game = Game::game_from_line("Game 3: 5 red, 1 blue; 3 red, 2 blue".into()).unwrap();
+        let result = Estimator::new(Sampling::WithoutReplacement, None, 20)
+            .estimate(&game)
+            .unwrap();
+        let sizes: Vec<usize> = result.posterior.iter().map(|p| p.0).collect();
+        assert_eq!(sizes, (7..=20).collect::<Vec<usize>>());
+        let mass: f64 = result.posterior.iter().map(|p| p.1).sum();
+        assert!((mass - 1.0).abs() < 1e-9);
+        // A bag that only just holds the draws makes drawing them unlikely, and one much
+        // larger makes drawing so few colours unlikely, whatever the cap
+        assert_eq!(result.most_likely_size().unwrap().0, 12);
+        assert!(!result.mode_at_cap());
+        let wider = Estimator::new(Sampling::WithoutReplacement, None, 40)
+            .estimate(&game)
+            .unwrap();
+        assert_eq!(wider.most_likely_size().unwrap().0, 12);
+    }
+
+    #[test]
+    fn test_mode_at_cap() {
+        let game = Game::game_from_line(
+            "Game 5: 5 red, 1 blue; 3 red, 2 blue; 6 red; 1 red, 1 blue; 6 blue".into(),
+        )
+        .unwrap();
+        let result = Estimator::new(Sampling::WithoutReplacement, None, 20)
+            .estimate(&game)
+            .unwrap();
+        assert_eq!(result.most_likely_size().unwrap().0, 20);
+        assert!(result.mode_at_cap());
+    }
+
+    #[test]
+    fn test_total_too_small() {
+        let game = Game::game_from_line("Game 4: 5 red, 1 blue".into()).unwrap();
+        let estimator = Estimator::new(Sampling::WithoutReplacement, Some(5), 100);
+        assert_eq!(estimator.estimate(&game), None);
+    }
+}
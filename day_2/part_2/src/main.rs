@@ -1,15 +1,98 @@
+mod error;
 mod game;
+mod inference;
 
 use crate::game::Game;
-use std::io::stdin;
+use crate::inference::{Estimator, Sampling};
+use clap::Parser;
+use std::collections::BTreeSet;
+use std::io::{stdin, BufRead, ErrorKind};
+
+#[derive(Parser)]
+struct Args {
+    /// Also estimate the bag each game was most likely played with
+    #[arg(long)]
+    infer: Option<Sampling>,
+    /// Number of cubes known to be in every bag
+    #[arg(long, requires = "infer")]
+    total: Option<usize>,
+    /// Largest bag considered when the total is unknown
+    #[arg(long, default_value_t = 60, requires = "infer")]
+    max_size: usize,
+}
 
 fn main() {
-    let lines = stdin().lines();
-    let games = lines.map(|l| Game::game_from_line(l.unwrap()));
+    let args = Args::parse();
+    let estimator = args
+        .infer
+        .map(|sampling| Estimator::new(sampling, args.total, args.max_size));
+    let games = read_games(stdin().lock());
+    let colours: BTreeSet<String> = games.iter().flat_map(Game::colours).collect();
 
     let mut total = 0;
     for game in games {
-        total += game.get_power();
-        println!("{}", total);
+        let power = game.get_power(&colours);
+        total += power;
+        println!(
+            "Game {}: power {} ({} cubes over {} rounds)",
+            game.id,
+            power,
+            game.total_cubes(),
+            game.rounds.len()
+        );
+        match estimator.as_ref().map(|e| e.estimate(&game)) {
+            Some(Some(estimate)) => {
+                let (size, probability) = estimate.most_likely_size().unwrap();
+                println!(
+                    "  most likely bag {}; most likely size {} (p = {:.3})",
+                    estimate.bag, size, probability
+                );
+                if estimate.mode_at_cap() {
+                    println!(
+                        "  size {} is the --max-size cap; larger bags are not ruled out",
+                        size
+                    );
+                }
+            }
+            Some(None) => println!("  no bag of an allowed size could have produced this game"),
+            None => {}
+        }
+    }
+    println!("{}", total);
+}
+
+// A malformed line is reported and skipped rather than ending the run
+fn read_games(input: impl BufRead) -> Vec<Game> {
+    let mut games = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(e) if e.kind() == ErrorKind::InvalidData => {
+                eprintln!("line {}: invalid UTF-8, skipped", index + 1);
+                continue;
+            }
+            Err(e) => {
+                eprintln!("line {}: {}", index + 1, e);
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match Game::game_from_line(line) {
+            Ok(game) => games.push(game),
+            Err(e) => eprintln!("line {}: {}, skipped", index + 1, e),
+        }
+    }
+    games
+}
+
+#[cfg(test)]
+mod test {
+    #[test]
+    fn test_read_games_skips_invalid_utf8_and_blank_lines() {
+        let input: &[u8] = b"Game 1: 3 blue\nGame 2: 3 bl\xffue\n\nGame 3: 1 red\n \n";
+        let ids: Vec<usize> = super::read_games(input).iter().map(|g| g.id).collect();
+        assert_eq!(ids, vec![1, 3]);
     }
 }
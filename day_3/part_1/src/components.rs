@@ -0,0 +1,159 @@
+use crate::part::Part;
+use crate::schematic::Schematic;
+use std::fmt::{Display, Formatter};
+
+// Zero-based and inclusive on every side
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BoundingBox {
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+}
+
+impl BoundingBox {
+    fn around(row: usize, left: usize, right: usize) -> Self {
+        Self {
+            top: row,
+            left,
+            bottom: row,
+            right,
+        }
+    }
+
+    fn union(self, other: BoundingBox) -> Self {
+        Self {
+            top: self.top.min(other.top),
+            left: self.left.min(other.left),
+            bottom: self.bottom.max(other.bottom),
+            right: self.right.max(other.right),
+        }
+    }
+}
+
+impl Display for BoundingBox {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "rows {}-{}, columns {}-{}",
+            self.top + 1,
+            self.bottom + 1,
+            self.left + 1,
+            self.right + 1
+        )
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Component {
+    pub parts: Vec<usize>,
+    pub symbols: Vec<usize>,
+    pub sum: usize,
+    // Covers the linking symbols as well as the parts
+    pub bounds: BoundingBox,
+}
+
+// Parts joined through symbols they both touch, found by walking the adjacency graph.
+// A part touching no symbol is not wired to anything and belongs to no component
+pub fn components(schematic: &Schematic) -> Vec<Component> {
+    let mut seen = vec![false; schematic.parts.len()];
+    let mut seen_symbols = vec![false; schematic.symbols.len()];
+    let mut out = Vec::new();
+    for start in schematic.valid_parts() {
+        if seen[start.id] {
+            continue;
+        }
+        seen[start.id] = true;
+        let mut parts = vec![start.id];
+        let mut symbols = Vec::new();
+        let mut next = 0;
+        while next < parts.len() {
+            for symbol in schematic.symbols_touching(parts[next]) {
+                if seen_symbols[symbol.id] {
+                    continue;
+                }
+                seen_symbols[symbol.id] = true;
+                symbols.push(symbol.id);
+                for part in schematic.parts_touching(symbol.id) {
+                    if !seen[part.id] {
+                        seen[part.id] = true;
+                        parts.push(part.id);
+                    }
+                }
+            }
+            next += 1;
+        }
+        parts.sort();
+        symbols.sort();
+        let bounds = parts
+            .iter()
+            .map(|id| &schematic.parts[*id])
+            .map(|p| BoundingBox::around(p.row, p.columns.start, p.columns.end - 1))
+            .chain(
+                symbols
+                    .iter()
+                    .map(|id| &schematic.symbols[*id])
+                    .map(|s| BoundingBox::around(s.row, s.column, s.column)),
+            )
+            .reduce(BoundingBox::union)
+            .unwrap();
+        out.push(Component {
+            sum: parts.iter().map(|id| schematic.parts[*id].number).sum(),
+            parts,
+            symbols,
+            bounds,
+        });
+    }
+    out
+}
+
+pub fn shared_parts(schematic: &Schematic) -> impl Iterator<Item = &Part> {
+    schematic
+        .parts
+        .iter()
+        .filter(|part| schematic.symbols_touching(part.id).len() > 1)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::components::{components, shared_parts, BoundingBox};
+    use crate::schematic::Schematic;
+
+    fn schematic(lines: &[&str]) -> Schematic {
+        let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        Schematic::from_lines(&lines)
+    }
+
+    #[test]
+    fn test_components_joined_through_a_shared_part() {
+        let result = schematic(&["1.2..9", "*.+..$", ".34...", "......"]);
+        let result = components(&result);
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].parts, vec![0, 1, 3]);
+        assert_eq!(result[0].sum, 37);
+        assert_eq!(
+            result[0].bounds,
+            BoundingBox {
+                top: 0,
+                left: 0,
+                bottom: 2,
+                right: 2
+            }
+        );
+        assert_eq!(result[1].parts, vec![2]);
+        assert_eq!(result[1].symbols, vec![2]);
+    }
+
+    #[test]
+    fn test_unwired_parts_are_left_out() {
+        let result = schematic(&["12...", "...7."]);
+        assert!(components(&result).is_empty());
+    }
+
+    #[test]
+    fn test_shared_parts() {
+        let result = schematic(&["1.2..9", "*.+..$", ".34...", "......"]);
+        let shared: Vec<usize> = shared_parts(&result).map(|p| p.number).collect();
+        assert_eq!(shared, vec![34]);
+    }
+}
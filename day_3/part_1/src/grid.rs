@@ -0,0 +1,74 @@
+use crate::part::Part;
+use std::num::NonZeroU32;
+
+// The part covering each cell of the schematic. IDs are stored one higher so an empty
+// cell costs no more than a full one, which matters on 10k by 10k schematics
+pub struct PartGrid {
+    width: usize,
+    cells: Vec<Option<NonZeroU32>>,
+}
+
+impl PartGrid {
+    pub fn new(rows: usize, width: usize, parts: &[Part]) -> Self {
+        let mut cells = vec![None; rows * width];
+        for part in parts {
+            let id = NonZeroU32::new(part.id as u32 + 1);
+            for column in part.columns.clone() {
+                cells[part.row * width + column] = id;
+            }
+        }
+        Self { width, cells }
+    }
+
+    pub fn at(&self, row: usize, column: usize) -> Option<usize> {
+        if column >= self.width {
+            return None;
+        }
+        self.cells
+            .get(row * self.width + column)
+            .copied()
+            .flatten()
+            .map(|id| id.get() as usize - 1)
+    }
+
+    // Each distinct part in the eight cells around a position, in reading order
+    pub fn around(&self, row: usize, column: usize) -> Vec<usize> {
+        let mut out: Vec<usize> = Vec::new();
+        for r in row.saturating_sub(1)..=row + 1 {
+            for c in column.saturating_sub(1)..=column + 1 {
+                if (r, c) == (row, column) {
+                    continue;
+                }
+                if let Some(id) = self.at(r, c) {
+                    if !out.contains(&id) {
+                        out.push(id);
+                    }
+                }
+            }
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::grid::PartGrid;
+    use crate::part::Part;
+
+    #[test]
+    fn test_around_counts_each_part_once() {
+        let parts = vec![Part::new(0, 467, 0, 0..3), Part::new(1, 35, 2, 2..4)];
+        let grid = PartGrid::new(3, 10, &parts);
+        assert_eq!(grid.around(1, 3), vec![0, 1]);
+        assert_eq!(grid.around(1, 8), Vec::<usize>::new());
+    }
+
+    #[test]
+    fn test_at_edges() {
+        let parts = vec![Part::new(0, 7, 0, 2..3)];
+        let grid = PartGrid::new(1, 3, &parts);
+        assert_eq!(grid.at(0, 2), Some(0));
+        assert_eq!(grid.at(0, 3), None);
+        assert_eq!(grid.around(0, 0), Vec::<usize>::new());
+    }
+}
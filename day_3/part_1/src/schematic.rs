@@ -0,0 +1,179 @@
+use crate::grid::PartGrid;
+use crate::part::Part;
+use crate::symbol::Symbol;
+
+// Parts and symbols form a bipartite graph: an edge joins each part to every symbol
+// in the cells around it. Both sides are numbered in reading order
+pub struct Schematic {
+    pub parts: Vec<Part>,
+    pub symbols: Vec<Symbol>,
+    part_symbols: Vec<Vec<usize>>,
+    symbol_parts: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    pub fn from_lines(lines: &[String]) -> Self {
+        let mut parts = Vec::new();
+        let mut symbols = Vec::new();
+        let mut width = 0;
+        for (row, line) in lines.iter().enumerate() {
+            let mut buffer = String::new();
+            let mut end = 0;
+            for (column, character) in line.chars().enumerate() {
+                end = column + 1;
+                if character.is_ascii_digit() {
+                    buffer.push(character);
+                    continue;
+                }
+                if !buffer.is_empty() {
+                    let number = buffer.parse::<usize>().unwrap_or(0);
+                    parts.push(Part::new(
+                        parts.len(),
+                        number,
+                        row,
+                        column - buffer.len()..column,
+                    ));
+                    buffer.clear();
+                }
+                if character != '.' {
+                    symbols.push(Symbol::new(symbols.len(), character, row, column));
+                }
+            }
+            if !buffer.is_empty() {
+                let number = buffer.parse::<usize>().unwrap_or(0);
+                parts.push(Part::new(parts.len(), number, row, end - buffer.len()..end));
+            }
+            width = width.max(end);
+        }
+
+        let mut part_symbols = vec![Vec::new(); parts.len()];
+        let mut symbol_parts = vec![Vec::new(); symbols.len()];
+        let grid = PartGrid::new(lines.len(), width, &parts);
+        for symbol in &symbols {
+            for part in grid.around(symbol.row, symbol.column) {
+                part_symbols[part].push(symbol.id);
+                symbol_parts[symbol.id].push(part);
+            }
+        }
+        Self {
+            parts,
+            symbols,
+            part_symbols,
+            symbol_parts,
+        }
+    }
+
+    pub fn symbols_touching(&self, part: usize) -> Vec<&Symbol> {
+        self.part_symbols
+            .get(part)
+            .map(|ids| ids.iter().map(|id| &self.symbols[*id]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn parts_touching(&self, symbol: usize) -> Vec<&Part> {
+        self.symbol_parts
+            .get(symbol)
+            .map(|ids| ids.iter().map(|id| &self.parts[*id]).collect())
+            .unwrap_or_default()
+    }
+
+    // A part number counts once however many symbols it touches
+    pub fn valid_parts(&self) -> impl Iterator<Item = &Part> {
+        self.parts
+            .iter()
+            .filter(|part| !self.part_symbols[part.id].is_empty())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::schematic::Schematic;
+
+    fn schematic(lines: &[&str]) -> Schematic {
+        let lines: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+        Schematic::from_lines(&lines)
+    }
+
+    fn numbers(schematic: &Schematic) -> Vec<usize> {
+        schematic.valid_parts().map(|p| p.number).collect()
+    }
+
+    #[test]
+    fn test_parse_both_empty() {
+        let result = schematic(&["."]);
+        assert!(result.parts.is_empty());
+        assert!(result.symbols.is_empty());
+    }
+
+    #[test]
+    fn test_parse_one_number() {
+        let result = schematic(&["1"]);
+        assert_eq!(result.parts[0].number, 1);
+        assert_eq!(result.parts[0].columns, 0..1);
+        assert!(result.symbols.is_empty());
+    }
+
+    #[test]
+    fn test_parse_one_symbol_hash() {
+        let result = schematic(&["#"]);
+        assert!(result.parts.is_empty());
+        assert_eq!(result.symbols[0].character, '#');
+    }
+
+    #[test]
+    fn test_parse_one_symbol_dollar() {
+        let result = schematic(&["$"]);
+        assert!(result.parts.is_empty());
+        assert_eq!(result.symbols[0].character, '$');
+    }
+
+    #[test]
+    fn test_parse_2_mix() {
+        let result = schematic(&["1$"]);
+        assert_eq!(result.parts[0].columns, 0..1);
+        assert_eq!(result.symbols[0].column, 1);
+        assert_eq!(numbers(&result), vec![1]);
+    }
+
+    #[test]
+    fn test_parse_3_mix() {
+        let result = schematic(&["12$3"]);
+        assert_eq!(result.parts[0].columns, 0..2);
+        assert_eq!(result.parts[1].columns, 3..4);
+        assert_eq!(numbers(&result), vec![12, 3]);
+    }
+
+    #[test]
+    fn test_vertical_above() {
+        assert_eq!(numbers(&schematic(&["*..", "12.", "..."])), vec![12]);
+    }
+
+    #[test]
+    fn test_vertical_below_diagonal() {
+        assert_eq!(numbers(&schematic(&["...", "12.", "..*"])), vec![12]);
+    }
+
+    #[test]
+    fn test_vertical_too_far() {
+        assert!(numbers(&schematic(&["*..", "...", "12."])).is_empty());
+    }
+
+    #[test]
+    fn test_horizontal_left() {
+        assert_eq!(numbers(&schematic(&["#7.."])), vec![7]);
+    }
+
+    #[test]
+    fn test_horizontal_too_far() {
+        assert!(numbers(&schematic(&["7..#"])).is_empty());
+    }
+
+    #[test]
+    fn test_adjacency_both_ways() {
+        let result = schematic(&["467..114..", "...*......", "..35..633."]);
+        let touching: Vec<usize> = result.parts_touching(0).iter().map(|p| p.number).collect();
+        assert_eq!(touching, vec![467, 35]);
+        assert_eq!(result.symbols_touching(0)[0].character, '*');
+        assert!(result.symbols_touching(1).is_empty());
+    }
+}
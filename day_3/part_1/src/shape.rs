@@ -0,0 +1,117 @@
+use std::fmt::{Display, Formatter};
+use std::io::BufRead;
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct RaggedRow {
+    pub line: usize,
+    pub length: usize,
+    pub expected: usize,
+}
+
+impl Display for RaggedRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {} is {} characters long, expected {}",
+            self.line, self.length, self.expected
+        )
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct UnreadableRow {
+    pub line: usize,
+    pub reason: String,
+}
+
+impl Display for UnreadableRow {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {} could not be read: {}", self.line, self.reason)
+    }
+}
+
+// Stops at the first row that cannot be read, since the rows after it would be misplaced
+pub fn read_rows(input: impl BufRead) -> Result<Vec<String>, UnreadableRow> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            line.map_err(|e| UnreadableRow {
+                line: index + 1,
+                reason: e.to_string(),
+            })
+        })
+        .collect()
+}
+
+// Trailing whitespace and blank lines are dropped, and lengths are counted in characters.
+// With `pad`, short rows are filled out with '.' to the longest row instead of rejected
+pub fn rectangular(lines: &[String], pad: bool) -> Result<Vec<String>, RaggedRow> {
+    let rows: Vec<(usize, &str)> = lines
+        .iter()
+        .map(|line| line.trim_end())
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .collect();
+    let lengths: Vec<usize> = rows.iter().map(|(_, row)| row.chars().count()).collect();
+    let expected = match pad {
+        true => lengths.iter().copied().max().unwrap_or(0),
+        false => lengths.first().copied().unwrap_or(0),
+    };
+    let mut out = Vec::new();
+    for ((index, row), length) in rows.into_iter().zip(lengths) {
+        if length == expected {
+            out.push(row.to_string());
+        } else if pad {
+            out.push(format!("{}{}", row, ".".repeat(expected - length)));
+        } else {
+            return Err(RaggedRow {
+                line: index + 1,
+                length,
+                expected,
+            });
+        }
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::shape::{read_rows, rectangular, RaggedRow};
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_ragged_row() {
+        let result = rectangular(&lines(&["467..", "", "..*", "35..."]), false);
+        assert_eq!(
+            result,
+            Err(RaggedRow {
+                line: 3,
+                length: 3,
+                expected: 5
+            })
+        );
+    }
+
+    #[test]
+    fn test_pad() {
+        let result = rectangular(&lines(&["467", "..*..", "35"]), true);
+        assert_eq!(result, Ok(lines(&["467..", "..*..", "35..."])));
+    }
+
+    #[test]
+    fn test_counts_characters() {
+        let result = rectangular(&lines(&["1×2 \t", "...\r", "\u{b7}..  "]), false);
+        assert_eq!(result, Ok(lines(&["1×2", "...", "\u{b7}.."])));
+    }
+
+    #[test]
+    fn test_unreadable_row() {
+        let input: &[u8] = b"467..\n...*.\n..3\xff.\n35...\n";
+        let result = read_rows(input).map_err(|e| e.line);
+        assert_eq!(result, Err(3));
+    }
+}
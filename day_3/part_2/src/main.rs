@@ -1,107 +1,98 @@
 extern crate core;
 
+mod grid;
 mod part;
+mod render;
+mod rule;
+mod schematic;
+mod shape;
+mod symbol;
 
-use crate::part::Part;
-use ndarray::{s, Array, Ix2};
-use std::io;
+use crate::render::{render, RenderFormat};
+use crate::rule::{Aggregation, GearRule, Neighbourhood, PartCount};
+use crate::schematic::Schematic;
+use crate::shape::{read_rows, rectangular};
+use clap::Parser;
+use std::{io, process};
 
-fn main() {
-    let reader = io::stdin();
-    let lines: Vec<String> = reader
-        .lines()
-        .filter_map(Result::ok)
-        .filter(|l| l.len() > 0)
-        .collect();
-
-    let (line_length, number_lines) = (lines[0].len(), lines.len());
-
-    let mut numbers: Array<Option<char>, Ix2> = Array::default((number_lines, line_length));
-    let mut valid: Array<bool, Ix2> = Array::default((number_lines, line_length));
-
-    for (i, line) in lines.iter().enumerate() {
-        let (temp_numbers, temp_valid) = get_matrices(line);
+#[derive(Parser)]
+struct Args {
+    /// Also list the symbols touching this part, numbered from 0 in reading order
+    #[arg(long)]
+    part: Option<usize>,
+    /// Also list the parts touching this symbol, numbered from 0 in reading order
+    #[arg(long)]
+    symbol: Option<usize>,
+    /// Fill short rows out with '.' instead of rejecting a ragged schematic
+    #[arg(long)]
+    pad: bool,
+    /// Characters that can be gears
+    #[arg(long, default_value = "*")]
+    gear_symbols: String,
+    /// Parts a gear must touch, exactly ("2") or as a range ("2-4")
+    #[arg(long, default_value = "2")]
+    gear_parts: PartCount,
+    /// How the numbers of a gear's parts combine into its value
+    #[arg(long, value_enum, default_value_t = Aggregation::Product)]
+    aggregate: Aggregation,
+    /// Which cells around a symbol count as touching it
+    #[arg(long, value_enum, default_value_t = Neighbourhood::Eight)]
+    neighbourhood: Neighbourhood,
+    /// Print the schematic coloured by part validity, symbols and gears instead of the total
+    #[arg(long, value_enum, conflicts_with_all = ["part", "symbol"])]
+    render: Option<RenderFormat>,
+}
 
-        numbers
-            .slice_mut(s![i, ..])
-            .assign(&Array::from(temp_numbers));
-        valid.slice_mut(s![i, ..]).assign(&Array::from(temp_valid));
+fn main() {
+    let args = Args::parse();
+    let lines = read_rows(io::stdin().lock()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    let lines = rectangular(&lines, args.pad).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    let rule = GearRule {
+        symbols: args.gear_symbols.chars().collect(),
+        parts: args.gear_parts,
+        aggregation: args.aggregate,
+    };
+    let schematic = Schematic::from_lines(&lines, args.neighbourhood);
+    if let Some(format) = args.render {
+        print!("{}", render(&lines, &schematic, &rule, format));
+        return;
     }
+    print_neighbours(&schematic, args.part, args.symbol);
 
-    let valid_locations: Vec<(usize, usize)> = valid
-        .indexed_iter()
-        .filter_map(|(l, v)| v.then_some(l))
-        .collect();
-
-    let mut parts = Vec::new();
-    for (i, row) in numbers.rows().into_iter().enumerate() {
-        let mut buffer = String::new();
-        let mut places = Vec::new();
+    let total = schematic
+        .gear_values(&rule)
+        .into_iter()
+        .fold(0, usize::saturating_add);
+    println!("{}", total);
+}
 
-        for (j, &place) in row.iter().enumerate() {
-            match place {
-                Some(character) => {
-                    buffer.push(character);
-                    places.push((i, j));
-                }
-                None => {
-                    if buffer.len() > 0 {
-                        parts.push(Part::new(buffer.parse::<usize>().unwrap(), places.clone()));
-                    }
-                    buffer.clear();
-                    places.clear();
+fn print_neighbours(schematic: &Schematic, part: Option<usize>, symbol: Option<usize>) {
+    if let Some(id) = part {
+        match schematic.parts.get(id) {
+            Some(part) => {
+                println!("Part {}: {}", id, part);
+                for symbol in schematic.symbols_touching(id) {
+                    println!("  touches {}", symbol);
                 }
             }
-        }
-        if buffer.len() > 0 {
-            parts.push(Part::new(buffer.parse::<usize>().unwrap(), places.clone()));
+            None => eprintln!("No part with ID {}", id),
         }
     }
-
-    let mut total: usize = 0;
-    let mut numbers = Vec::new();
-    for (x, y) in valid_locations {
-        for part in &parts {
-            let min = part
-                .character_places
-                .iter()
-                .map(|(a, b)| euclid_distance(*a as i32, *b as i32, x as i32, y as i32))
-                .min_by(|a, b| a.partial_cmp(b).unwrap())
-                .unwrap();
-            if min <= 1.5f64 {
-                numbers.push(part.number);
+    if let Some(id) = symbol {
+        match schematic.symbols.get(id) {
+            Some(symbol) => {
+                println!("Symbol {}: {}", id, symbol);
+                for part in schematic.parts_touching(id) {
+                    println!("  touches {}", part);
+                }
             }
-        }
-        if numbers.len() == 2 {
-            total += numbers.iter().product::<usize>();
-        }
-        numbers.clear();
-    }
-
-    println!("{}", total);
-}
-
-fn euclid_distance(a: i32, b: i32, x: i32, y: i32) -> f64 {
-    (((a - x) as f64).powi(2) + ((b - y) as f64).powi(2)).sqrt()
-}
-
-fn get_matrices(string: &String) -> (Vec<Option<char>>, Vec<bool>) {
-    let mut characters: Vec<Option<char>> = Vec::new();
-    let mut symbols = Vec::new();
-    for char in string.as_bytes().iter().map(|c| char::from(*c)) {
-        if char == '.' {
-            characters.push(None);
-            symbols.push(false);
-        } else if char.is_digit(10) {
-            characters.push(Some(char));
-            symbols.push(false);
-        } else if char == '*' {
-            characters.push(None);
-            symbols.push(true);
-        } else {
-            characters.push(None);
-            symbols.push(false);
+            None => eprintln!("No symbol with ID {}", id),
         }
     }
-    (characters, symbols)
 }
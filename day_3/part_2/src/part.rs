@@ -1,13 +1,31 @@
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Part {
+    pub id: usize,
     pub number: usize,
-    pub character_places: Vec<(usize, usize)>,
+    pub row: usize,
+    pub columns: Range<usize>,
 }
 
 impl Part {
-    pub fn new(number: usize, character_places: Vec<(usize, usize)>) -> Self {
+    pub fn new(id: usize, number: usize, row: usize, columns: Range<usize>) -> Self {
         Self {
+            id,
             number,
-            character_places,
+            row,
+            columns,
+        }
+    }
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at row {}, ", self.number, self.row + 1)?;
+        match self.columns.len() {
+            1 => write!(f, "column {}", self.columns.end),
+            _ => write!(f, "columns {}-{}", self.columns.start + 1, self.columns.end),
         }
     }
 }
@@ -0,0 +1,192 @@
+use crate::rule::GearRule;
+use crate::schematic::Schematic;
+use clap::ValueEnum;
+
+const VALID_COLOUR: &str = "\x1b[32m";
+const INVALID_COLOUR: &str = "\x1b[31m";
+const SYMBOL_COLOUR: &str = "\x1b[33m";
+const GEAR_COLOUR: &str = "\x1b[1;35m";
+const RESET: &str = "\x1b[0m";
+
+const HTML_HEAD: &str = "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>Schematic</title>
+<style>
+body { background: #111; color: #777; }
+.valid { color: #5c5; }
+.invalid { color: #e55; }
+.symbol { color: #dd5; }
+.gear { color: #e5e; font-weight: bold; }
+</style>
+</head>
+<body>
+<pre>
+";
+const HTML_TAIL: &str = "</pre>
+</body>
+</html>
+";
+
+#[derive(ValueEnum, Debug, Clone, Copy, Eq, PartialEq)]
+pub enum RenderFormat {
+    /// Colours for a terminal
+    Ansi,
+    /// A standalone HTML page
+    Html,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Style {
+    Valid,
+    Invalid,
+    Symbol,
+    Gear,
+}
+
+impl Style {
+    fn colour(&self) -> &'static str {
+        match self {
+            Style::Valid => VALID_COLOUR,
+            Style::Invalid => INVALID_COLOUR,
+            Style::Symbol => SYMBOL_COLOUR,
+            Style::Gear => GEAR_COLOUR,
+        }
+    }
+
+    fn class(&self) -> &'static str {
+        match self {
+            Style::Valid => "valid",
+            Style::Invalid => "invalid",
+            Style::Symbol => "symbol",
+            Style::Gear => "gear",
+        }
+    }
+}
+
+// Re-prints the schematic with valid and invalid part numbers, symbols and gears told apart
+pub fn render(
+    lines: &[String],
+    schematic: &Schematic,
+    rule: &GearRule,
+    format: RenderFormat,
+) -> String {
+    let styles = styles(lines, schematic, rule);
+    let runs = lines.iter().zip(&styles).map(|(line, row)| runs(line, row));
+    match format {
+        RenderFormat::Ansi => runs
+            .map(|line| {
+                let mut out = String::new();
+                for (style, text) in line {
+                    match style {
+                        Some(style) => {
+                            out.push_str(style.colour());
+                            out.push_str(&text);
+                            out.push_str(RESET);
+                        }
+                        None => out.push_str(&text),
+                    }
+                }
+                out + "\n"
+            })
+            .collect(),
+        RenderFormat::Html => {
+            let mut out = HTML_HEAD.to_string();
+            for line in runs {
+                for (style, text) in line {
+                    match style {
+                        Some(style) => out.push_str(&format!(
+                            "<span class=\"{}\">{}</span>",
+                            style.class(),
+                            escape(&text)
+                        )),
+                        None => out.push_str(&escape(&text)),
+                    }
+                }
+                out.push('\n');
+            }
+            out + HTML_TAIL
+        }
+    }
+}
+
+fn styles(lines: &[String], schematic: &Schematic, rule: &GearRule) -> Vec<Vec<Option<Style>>> {
+    let mut out: Vec<Vec<Option<Style>>> = lines
+        .iter()
+        .map(|l| vec![None; l.chars().count()])
+        .collect();
+    for part in &schematic.parts {
+        let style = match schematic.symbols_touching(part.id).is_empty() {
+            true => Style::Invalid,
+            false => Style::Valid,
+        };
+        for column in part.columns.clone() {
+            out[part.row][column] = Some(style);
+        }
+    }
+    for symbol in &schematic.symbols {
+        out[symbol.row][symbol.column] = match schematic.is_gear(symbol, rule) {
+            true => Some(Style::Gear),
+            false => Some(Style::Symbol),
+        };
+    }
+    out
+}
+
+// Neighbouring characters of the same style share one run
+fn runs(line: &str, styles: &[Option<Style>]) -> Vec<(Option<Style>, String)> {
+    let mut out: Vec<(Option<Style>, String)> = Vec::new();
+    for (character, style) in line.chars().zip(styles) {
+        match out.last_mut() {
+            Some((current, text)) if current == style => text.push(character),
+            _ => out.push((*style, character.to_string())),
+        }
+    }
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod test {
+    use crate::render::{render, RenderFormat};
+    use crate::rule::{GearRule, Neighbourhood};
+    use crate::schematic::Schematic;
+
+    fn lines() -> Vec<String> {
+        vec!["12.7".to_string(), "*..&".to_string(), "3...".to_string()]
+    }
+
+    #[test]
+    fn test_render_ansi() {
+        let schematic = Schematic::from_lines(&lines(), Neighbourhood::Eight);
+        let result = render(
+            &lines(),
+            &schematic,
+            &GearRule::default(),
+            RenderFormat::Ansi,
+        );
+        assert_eq!(
+            result,
+            "\x1b[32m12\x1b[0m.\x1b[32m7\x1b[0m\n\
+             \x1b[1;35m*\x1b[0m..\x1b[33m&\x1b[0m\n\
+             \x1b[32m3\x1b[0m...\n"
+        );
+    }
+
+    #[test]
+    fn test_render_html() {
+        let lines = vec!["5..&".to_string()];
+        let schematic = Schematic::from_lines(&lines, Neighbourhood::Eight);
+        let result = render(&lines, &schematic, &GearRule::default(), RenderFormat::Html);
+        assert!(result.starts_with("<!DOCTYPE html>"));
+        assert!(result
+            .contains("<span class=\"invalid\">5</span>..<span class=\"symbol\">&amp;</span>\n"));
+        assert!(result.ends_with("</html>\n"));
+    }
+}
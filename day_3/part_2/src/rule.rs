@@ -0,0 +1,148 @@
+use clap::ValueEnum;
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum Neighbourhood {
+    /// Only the cells above, below, left and right
+    #[value(name = "4")]
+    Four,
+    /// Diagonal cells as well
+    #[value(name = "8")]
+    Eight,
+}
+
+impl Neighbourhood {
+    pub fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighbourhood::Four => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Neighbourhood::Eight => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, ValueEnum)]
+pub enum Aggregation {
+    Product,
+    Sum,
+    Max,
+}
+
+impl Aggregation {
+    // A gear with no parts is worth nothing, where an empty product would make it 1.
+    // Products and sums stop at usize::MAX rather than overflow
+    pub fn apply(&self, numbers: impl Iterator<Item = usize>) -> usize {
+        let mut numbers = numbers.peekable();
+        if numbers.peek().is_none() {
+            return 0;
+        }
+        match self {
+            Aggregation::Product => numbers.fold(1, usize::saturating_mul),
+            Aggregation::Sum => numbers.fold(0, usize::saturating_add),
+            Aggregation::Max => numbers.max().unwrap_or(0),
+        }
+    }
+}
+
+// How many parts a gear touches, written as "2" for exactly two or "2-4" for a range
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PartCount(pub RangeInclusive<usize>);
+
+impl PartCount {
+    pub fn contains(&self, count: usize) -> bool {
+        self.0.contains(&count)
+    }
+}
+
+impl FromStr for PartCount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = |n: &str| {
+            n.trim()
+                .parse::<usize>()
+                .map_err(|_| format!("expected a part count such as 2 or 2-4, found {:?}", s))
+        };
+        let (low, high) = match s.split_once('-') {
+            Some((low, high)) => (parse(low)?, parse(high)?),
+            None => (parse(s)?, parse(s)?),
+        };
+        if low > high {
+            return Err(format!("empty part count range {:?}", s));
+        }
+        Ok(PartCount(low..=high))
+    }
+}
+
+impl Display for PartCount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.0.start() == self.0.end() {
+            true => write!(f, "{}", self.0.start()),
+            false => write!(f, "{}-{}", self.0.start(), self.0.end()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GearRule {
+    pub symbols: Vec<char>,
+    pub parts: PartCount,
+    pub aggregation: Aggregation,
+}
+
+// The puzzle's rule: a `*` touching exactly two parts, multiplied
+impl Default for GearRule {
+    fn default() -> Self {
+        Self {
+            symbols: vec!['*'],
+            parts: PartCount(2..=2),
+            aggregation: Aggregation::Product,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rule::{Aggregation, PartCount};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_part_count_from_str() {
+        assert_eq!(PartCount::from_str("2"), Ok(PartCount(2..=2)));
+        assert_eq!(PartCount::from_str("1-3"), Ok(PartCount(1..=3)));
+        assert!(PartCount::from_str("3-1").is_err());
+        assert!(PartCount::from_str("two").is_err());
+    }
+
+    #[test]
+    fn test_aggregation() {
+        let numbers = [467, 35];
+        assert_eq!(Aggregation::Product.apply(numbers.into_iter()), 16345);
+        assert_eq!(Aggregation::Sum.apply(numbers.into_iter()), 502);
+        assert_eq!(Aggregation::Max.apply(numbers.into_iter()), 467);
+    }
+
+    #[test]
+    fn test_aggregation_no_parts() {
+        assert_eq!(Aggregation::Product.apply(std::iter::empty()), 0);
+        assert_eq!(Aggregation::Sum.apply(std::iter::empty()), 0);
+    }
+
+    #[test]
+    fn test_aggregation_saturates() {
+        let numbers = [9999999999, 9999999999];
+        assert_eq!(Aggregation::Product.apply(numbers.into_iter()), usize::MAX);
+        let numbers = [usize::MAX, 1];
+        assert_eq!(Aggregation::Sum.apply(numbers.into_iter()), usize::MAX);
+    }
+}
@@ -0,0 +1,157 @@
+use crate::grid::PartGrid;
+use crate::part::Part;
+use crate::rule::{GearRule, Neighbourhood};
+use crate::symbol::Symbol;
+
+// Parts and symbols form a bipartite graph: an edge joins each part to every symbol
+// in the cells around it. Both sides are numbered in reading order
+pub struct Schematic {
+    pub parts: Vec<Part>,
+    pub symbols: Vec<Symbol>,
+    part_symbols: Vec<Vec<usize>>,
+    symbol_parts: Vec<Vec<usize>>,
+}
+
+impl Schematic {
+    pub fn from_lines(lines: &[String], neighbourhood: Neighbourhood) -> Self {
+        let mut parts = Vec::new();
+        let mut symbols = Vec::new();
+        let mut width = 0;
+        for (row, line) in lines.iter().enumerate() {
+            let mut buffer = String::new();
+            let mut end = 0;
+            for (column, character) in line.chars().enumerate() {
+                end = column + 1;
+                if character.is_ascii_digit() {
+                    buffer.push(character);
+                    continue;
+                }
+                if !buffer.is_empty() {
+                    let number = buffer.parse::<usize>().unwrap_or(0);
+                    parts.push(Part::new(
+                        parts.len(),
+                        number,
+                        row,
+                        column - buffer.len()..column,
+                    ));
+                    buffer.clear();
+                }
+                if character != '.' {
+                    symbols.push(Symbol::new(symbols.len(), character, row, column));
+                }
+            }
+            if !buffer.is_empty() {
+                let number = buffer.parse::<usize>().unwrap_or(0);
+                parts.push(Part::new(parts.len(), number, row, end - buffer.len()..end));
+            }
+            width = width.max(end);
+        }
+
+        let mut part_symbols = vec![Vec::new(); parts.len()];
+        let mut symbol_parts = vec![Vec::new(); symbols.len()];
+        let grid = PartGrid::new(lines.len(), width, &parts);
+        for symbol in &symbols {
+            for part in grid.around(symbol.row, symbol.column, neighbourhood) {
+                part_symbols[part].push(symbol.id);
+                symbol_parts[symbol.id].push(part);
+            }
+        }
+        Self {
+            parts,
+            symbols,
+            part_symbols,
+            symbol_parts,
+        }
+    }
+
+    pub fn symbols_touching(&self, part: usize) -> Vec<&Symbol> {
+        self.part_symbols
+            .get(part)
+            .map(|ids| ids.iter().map(|id| &self.symbols[*id]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn parts_touching(&self, symbol: usize) -> Vec<&Part> {
+        self.symbol_parts
+            .get(symbol)
+            .map(|ids| ids.iter().map(|id| &self.parts[*id]).collect())
+            .unwrap_or_default()
+    }
+
+    pub fn is_gear(&self, symbol: &Symbol, rule: &GearRule) -> bool {
+        rule.symbols.contains(&symbol.character)
+            && rule.parts.contains(self.symbol_parts[symbol.id].len())
+    }
+
+    pub fn gear_values(&self, rule: &GearRule) -> Vec<usize> {
+        self.symbols
+            .iter()
+            .filter(|symbol| self.is_gear(symbol, rule))
+            .map(|symbol| {
+                let parts = self.parts_touching(symbol.id);
+                rule.aggregation.apply(parts.iter().map(|p| p.number))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::rule::{Aggregation, GearRule, Neighbourhood, PartCount};
+    use crate::schematic::Schematic;
+
+    fn example() -> Schematic {
+        let lines: Vec<String> = [
+            "467..114..",
+            "...*......",
+            "..35..633.",
+            "......#...",
+            "617*......",
+            ".....+.58.",
+            "..592.....",
+            "......755.",
+            "...$.*....",
+            ".664.598..",
+        ]
+        .iter()
+        .map(|l| l.to_string())
+        .collect();
+        Schematic::from_lines(&lines, Neighbourhood::Eight)
+    }
+
+    #[test]
+    fn test_gear_ratios() {
+        let result = example().gear_values(&GearRule::default());
+        assert_eq!(result, vec![16345, 451490]);
+    }
+
+    #[test]
+    fn test_custom_rule() {
+        let rule = GearRule {
+            symbols: vec!['*', '+', '#'],
+            parts: PartCount(1..=2),
+            aggregation: Aggregation::Max,
+        };
+        let result = example().gear_values(&rule);
+        assert_eq!(result, vec![467, 633, 617, 592, 755]);
+    }
+
+    #[test]
+    fn test_star_with_one_part_is_not_a_gear() {
+        let result = example();
+        let star = result.symbols.iter().find(|s| s.row == 4).unwrap();
+        assert_eq!(result.parts_touching(star.id).len(), 1);
+    }
+
+    #[test]
+    fn test_symbols_touching() {
+        let result = example();
+        let part = result.parts.iter().find(|p| p.number == 592).unwrap();
+        let symbols: Vec<char> = result
+            .symbols_touching(part.id)
+            .iter()
+            .map(|s| s.character)
+            .collect();
+        assert_eq!(symbols, vec!['+']);
+    }
+}
@@ -0,0 +1,32 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Symbol {
+    pub id: usize,
+    pub character: char,
+    pub row: usize,
+    pub column: usize,
+}
+
+impl Symbol {
+    pub fn new(id: usize, character: char, row: usize, column: usize) -> Self {
+        Self {
+            id,
+            character,
+            row,
+            column,
+        }
+    }
+}
+
+impl Display for Symbol {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} at row {}, column {}",
+            self.character,
+            self.row + 1,
+            self.column + 1
+        )
+    }
+}
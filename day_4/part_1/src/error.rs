@@ -0,0 +1,18 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TicketParseError {
+    MissingHeader,
+    MissingBar,
+    BadNumber(String),
+}
+
+impl Display for TicketParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketParseError::MissingHeader => write!(f, "missing \"Card N:\" header"),
+            TicketParseError::MissingBar => write!(f, "missing '|' between the two lists"),
+            TicketParseError::BadNumber(text) => write!(f, "{:?} is not a number", text),
+        }
+    }
+}
@@ -1,15 +1,50 @@
-use crate::ticket::Ticket;
+use crate::scoring::Scoring;
+use crate::ticket::{Duplicates, Ticket};
+use clap::Parser;
 use std::io::stdin;
+use std::process;
 
+mod error;
+mod scoring;
 mod ticket;
 
+#[derive(Parser)]
+struct Args {
+    /// Whether a drawn number repeated on a card matches every time or only once
+    #[arg(long, value_enum, default_value_t = Duplicates::EveryTime)]
+    duplicates: Duplicates,
+    /// How a card's matches turn into points: doubling, linear, fibonacci or table:1,3,5
+    #[arg(long, default_value_t = Scoring::Doubling)]
+    scoring: Scoring,
+}
+
 fn main() {
-    let tickets: Vec<Ticket> = stdin()
-        .lines()
-        .filter_map(Result::ok)
-        .filter(|l| l.len() > 0)
-        .map(|l| Ticket::from_string(l))
-        .collect();
-    let total: i32 = tickets.iter().map(|t| t.calculate_worth()).sum();
+    let args = Args::parse();
+    let tickets = read_tickets(args.duplicates);
+    let rule = args.scoring.rule();
+    let total: i64 = tickets
+        .iter()
+        .map(|t| t.calculate_worth(rule.as_ref()) as i64)
+        .sum();
     println!("{}", total);
 }
+
+// A malformed line is reported and skipped rather than ending the run, but a line that
+// cannot be read at all ends it, since the card count would be wrong from there on
+fn read_tickets(duplicates: Duplicates) -> Vec<Ticket> {
+    let mut tickets = Vec::new();
+    for (index, line) in stdin().lines().enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("line {}: {}", index + 1, e);
+            process::exit(1)
+        });
+        if line.is_empty() {
+            continue;
+        }
+        match Ticket::from_string(line) {
+            Ok(ticket) => tickets.push(ticket.with_duplicates(duplicates)),
+            Err(e) => eprintln!("line {}: {}, skipped", index + 1, e),
+        }
+    }
+    tickets
+}
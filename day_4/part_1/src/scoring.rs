@@ -0,0 +1,160 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+// What a card is worth for the number of its drawn numbers that match. Built-in rules
+// stop at i32::MAX rather than overflow
+pub trait ScoringRule {
+    fn score(&self, matches: usize) -> i32;
+}
+
+// The puzzle's rule: one point for the first match, doubled for each after it
+pub struct Doubling;
+
+impl ScoringRule for Doubling {
+    fn score(&self, matches: usize) -> i32 {
+        match matches {
+            0 => 0,
+            n => u32::try_from(n - 1)
+                .ok()
+                .and_then(|power| 2_i32.checked_pow(power))
+                .unwrap_or(i32::MAX),
+        }
+    }
+}
+
+pub struct Linear;
+
+impl ScoringRule for Linear {
+    fn score(&self, matches: usize) -> i32 {
+        i32::try_from(matches).unwrap_or(i32::MAX)
+    }
+}
+
+// Each match is worth the last two together, starting from one and two
+pub struct Fibonacci;
+
+impl ScoringRule for Fibonacci {
+    fn score(&self, matches: usize) -> i32 {
+        if matches == 0 {
+            return 0;
+        }
+        let (mut before, mut worth) = (1_i32, 1_i32);
+        for _ in 1..matches {
+            (before, worth) = (worth, before.saturating_add(worth));
+        }
+        worth
+    }
+}
+
+// Worth looked up by match count, starting from one match. Counts past the end of the
+// table are worth its last entry
+pub struct Table(pub Vec<i32>);
+
+impl ScoringRule for Table {
+    fn score(&self, matches: usize) -> i32 {
+        match matches {
+            0 => 0,
+            n => self.0.get(n - 1).or(self.0.last()).copied().unwrap_or(0),
+        }
+    }
+}
+
+// A rule chosen on the command line, written as doubling, linear, fibonacci or
+// table:1,3,5 with the worth of one, two and three or more matches
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Scoring {
+    Doubling,
+    Linear,
+    Fibonacci,
+    Table(Vec<i32>),
+}
+
+impl Scoring {
+    pub fn rule(&self) -> Box<dyn ScoringRule> {
+        match self {
+            Scoring::Doubling => Box::new(Doubling),
+            Scoring::Linear => Box::new(Linear),
+            Scoring::Fibonacci => Box::new(Fibonacci),
+            Scoring::Table(table) => Box::new(Table(table.clone())),
+        }
+    }
+}
+
+impl FromStr for Scoring {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some(("table", entries)) => entries
+                .split(',')
+                .map(|n| {
+                    n.trim()
+                        .parse::<i32>()
+                        .map_err(|_| format!("bad table entry {:?}", n))
+                })
+                .collect::<Result<Vec<_>, _>>()
+                .map(Scoring::Table),
+            _ => match s {
+                "doubling" => Ok(Scoring::Doubling),
+                "linear" => Ok(Scoring::Linear),
+                "fibonacci" => Ok(Scoring::Fibonacci),
+                _ => Err(format!(
+                    "expected doubling, linear, fibonacci or table:N,N,..., found {:?}",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+impl Display for Scoring {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Scoring::Doubling => write!(f, "doubling"),
+            Scoring::Linear => write!(f, "linear"),
+            Scoring::Fibonacci => write!(f, "fibonacci"),
+            Scoring::Table(table) => {
+                let entries: Vec<String> = table.iter().map(|n| n.to_string()).collect();
+                write!(f, "table:{}", entries.join(","))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::scoring::{Doubling, Fibonacci, Linear, Scoring, ScoringRule, Table};
+    use std::str::FromStr;
+
+    fn scores(rule: &dyn ScoringRule) -> Vec<i32> {
+        (0..6).map(|n| rule.score(n)).collect()
+    }
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(scores(&Doubling), vec![0, 1, 2, 4, 8, 16]);
+        assert_eq!(scores(&Linear), vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(scores(&Fibonacci), vec![0, 1, 2, 3, 5, 8]);
+        assert_eq!(scores(&Table(vec![1, 3, 5])), vec![0, 1, 3, 5, 5, 5]);
+    }
+
+    #[test]
+    fn test_rules_saturate() {
+        assert_eq!(Doubling.score(31), 1 << 30);
+        assert_eq!(Doubling.score(32), i32::MAX);
+        assert_eq!(Fibonacci.score(100), i32::MAX);
+        assert_eq!(Linear.score(usize::MAX), i32::MAX);
+    }
+
+    #[test]
+    fn test_scoring_from_str() {
+        assert_eq!(Scoring::from_str("linear"), Ok(Scoring::Linear));
+        assert_eq!(
+            Scoring::from_str("table:1, 3,5"),
+            Ok(Scoring::Table(vec![1, 3, 5]))
+        );
+        assert_eq!(Scoring::Table(vec![1, 3, 5]).to_string(), "table:1,3,5");
+        assert!(Scoring::from_str("table:").is_err());
+        assert!(Scoring::from_str("squares").is_err());
+    }
+}
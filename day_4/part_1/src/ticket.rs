@@ -1,79 +1,148 @@
+use crate::error::TicketParseError;
+use crate::scoring::ScoringRule;
+use clap::ValueEnum;
+use std::collections::HashSet;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, ValueEnum)]
+pub enum Duplicates {
+    /// A drawn number that appears twice matches twice
+    #[default]
+    EveryTime,
+    /// A drawn number matches at most once however often it appears
+    Once,
+}
+
 pub struct Ticket {
-    winning_numbers: Vec<i32>,
+    winning_numbers: HashSet<i32>,
     all_numbers: Vec<i32>,
+    duplicates: Duplicates,
 }
 
 impl Ticket {
     pub fn new(winning_numbers: Vec<i32>, all_numbers: Vec<i32>) -> Self {
         Self {
-            winning_numbers,
+            winning_numbers: winning_numbers.into_iter().collect(),
             all_numbers,
+            duplicates: Duplicates::default(),
         }
     }
-    pub fn calculate_worth(&self) -> i32 {
-        let mut n: i32 = -1;
-        for number in &self.all_numbers {
-            if self.winning_numbers.contains(&number) {
-                n += 1;
-            }
-        }
-        if n >= 0 {
-            return 2_i32.pow(n as u32) as i32;
-        }
-        0
+
+    pub fn with_duplicates(self, duplicates: Duplicates) -> Self {
+        Self { duplicates, ..self }
     }
-    pub fn from_string(input: String) -> Self {
-        let parts: Vec<String> = input.split([':', '|']).map(|s| s.to_string()).collect();
-        Self {
-            winning_numbers: get_numbers(&parts[1]),
-            all_numbers: get_numbers(&parts[2]),
-        }
+    pub fn calculate_worth(&self, rule: &dyn ScoringRule) -> i32 {
+        rule.score(self.matches())
+    }
+
+    fn matches(&self) -> usize {
+        let mut seen = HashSet::new();
+        self.all_numbers
+            .iter()
+            .filter(|n| self.winning_numbers.contains(n))
+            .filter(|n| self.duplicates == Duplicates::EveryTime || seen.insert(**n))
+            .count()
+    }
+
+    pub fn from_string(input: String) -> Result<Self, TicketParseError> {
+        let (header, numbers) = input
+            .split_once(':')
+            .ok_or(TicketParseError::MissingHeader)?;
+        // Only the numbers matter here, but the header still has to be there
+        header
+            .trim()
+            .strip_prefix("Card")
+            .and_then(|id| id.trim().parse::<i32>().ok())
+            .ok_or(TicketParseError::MissingHeader)?;
+        let (winning, all) = numbers
+            .split_once('|')
+            .ok_or(TicketParseError::MissingBar)?;
+        Ok(Self::new(get_numbers(winning)?, get_numbers(all)?))
     }
 }
 
-fn get_numbers(input: &String) -> Vec<i32> {
+fn get_numbers(input: &str) -> Result<Vec<i32>, TicketParseError> {
     input
-        .split(' ')
-        .filter_map(|p| p.parse::<i32>().ok())
+        .split_whitespace()
+        .map(|p| {
+            p.parse::<i32>()
+                .map_err(|_| TicketParseError::BadNumber(p.to_string()))
+        })
         .collect()
 }
 
 #[cfg(test)]
 mod test {
-    use crate::ticket::Ticket;
+    use crate::error::TicketParseError;
+    use crate::scoring::{Doubling, Table};
+    use crate::ticket::{Duplicates, Ticket};
 
     #[test]
     fn test_worth_none() {
         let test = Ticket::new(Vec::new(), Vec::new());
-        let result = test.calculate_worth();
+        let result = test.calculate_worth(&Doubling);
         assert_eq!(result, 0)
     }
 
     #[test]
     fn test_worth_no_match() {
         let test = Ticket::new(vec![1], vec![0]);
-        let result = test.calculate_worth();
+        let result = test.calculate_worth(&Doubling);
         assert_eq!(result, 0)
     }
 
     #[test]
     fn test_worth_one_match() {
         let test = Ticket::new(vec![1], vec![0, 1]);
-        let result = test.calculate_worth();
+        let result = test.calculate_worth(&Doubling);
         assert_eq!(result, 1)
     }
 
     #[test]
     fn test_worth_two_matches_same() {
         let test = Ticket::new(vec![1], vec![0, 1, 1]);
-        let result = test.calculate_worth();
+        let result = test.calculate_worth(&Doubling);
         assert_eq!(result, 2)
     }
 
     #[test]
     fn test_worth_two_matches_different() {
         let test = Ticket::new(vec![1, 2], vec![0, 1, 2]);
-        let result = test.calculate_worth();
+        let result = test.calculate_worth(&Doubling);
         assert_eq!(result, 2)
     }
+
+    #[test]
+    fn test_worth_two_matches_same_once() {
+        let test = Ticket::new(vec![1], vec![0, 1, 1]).with_duplicates(Duplicates::Once);
+        let result = test.calculate_worth(&Doubling);
+        assert_eq!(result, 1)
+    }
+
+    #[test]
+    fn test_worth_by_table() {
+        let test = Ticket::new(vec![1, 2, 3], vec![3, 2, 1, 4]);
+        let result = test.calculate_worth(&Table(vec![1, 3]));
+        assert_eq!(result, 3)
+    }
+
+    #[test]
+    fn test_from_string_errors() {
+        let parse = |line: &str| Ticket::from_string(line.to_string()).err();
+        assert_eq!(
+            parse("41 48 | 83 86"),
+            Some(TicketParseError::MissingHeader)
+        );
+        assert_eq!(
+            parse("Card x: 41 48 | 83 86"),
+            Some(TicketParseError::MissingHeader)
+        );
+        assert_eq!(
+            parse("Card 1: 41 48 83 86"),
+            Some(TicketParseError::MissingBar)
+        );
+        assert_eq!(
+            parse("Card 1: 41 48 | 83 8b6"),
+            Some(TicketParseError::BadNumber("8b6".to_string()))
+        );
+    }
 }
@@ -0,0 +1,245 @@
+use crate::error::CascadeError;
+use crate::scoring::ScoringRule;
+use crate::ticket::Ticket;
+use clap::ValueEnum;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, ValueEnum)]
+pub enum PastLastCard {
+    /// Stop with an error
+    #[default]
+    Error,
+    /// Copies of cards past the last one are not won
+    Clamp,
+    /// Counting carries on from the first card
+    Wrap,
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Cascade {
+    pub total: usize,
+    // Copies held of each card, original included, in the order the cards were given
+    pub copies: Vec<usize>,
+}
+
+impl Cascade {
+    // Every copy scores as its original would, given the same cards the cascade was run on
+    pub fn worth(&self, tickets: &[Ticket], rule: &dyn ScoringRule) -> Result<i64, CascadeError> {
+        tickets
+            .iter()
+            .zip(&self.copies)
+            .try_fold(0_i64, |total, (ticket, copies)| {
+                i64::try_from(*copies)
+                    .ok()
+                    .and_then(|copies| copies.checked_mul(ticket.calculate_worth(rule) as i64))
+                    .and_then(|worth| total.checked_add(worth))
+                    .ok_or(CascadeError::Overflow(ticket.ticket_id))
+            })
+    }
+}
+
+// Cards are placed in ID order and win the cards in the places after their own, so a
+// deck with a card missing still chains together. Every copy of a card is won before it
+// passes its own copies on
+pub fn cascade(tickets: &[Ticket], past_end: PastLastCard) -> Result<Cascade, CascadeError> {
+    let mut order: Vec<usize> = (0..tickets.len()).collect();
+    order.sort_by_key(|i| tickets[*i].ticket_id);
+    let places = order.len();
+
+    let mut wins: Vec<Vec<usize>> = vec![Vec::new(); places];
+    for (place, i) in order.iter().enumerate() {
+        let matches = tickets[*i].get_winning_numbers().len();
+        let past = (place + matches + 1).saturating_sub(places);
+        if past > 0 && past_end == PastLastCard::Error {
+            return Err(CascadeError::PastLastCard(tickets[*i].ticket_id, past));
+        }
+        for won in place + 1..=place + matches {
+            match past_end {
+                _ if won < places => wins[place].push(won),
+                PastLastCard::Wrap => wins[place].push(won % places),
+                _ => {}
+            }
+        }
+    }
+
+    let place_id = |place: usize| tickets[order[place]].ticket_id;
+    let copies = match past_end {
+        PastLastCard::Wrap => copies_by_graph(&wins, place_id)?,
+        _ => copies_in_order(&wins, place_id)?,
+    };
+
+    let mut out = vec![0; places];
+    let mut total: usize = 0;
+    for (place, i) in order.iter().enumerate() {
+        out[*i] = copies[place];
+        total = total
+            .checked_add(copies[place])
+            .ok_or(CascadeError::Overflow(tickets[*i].ticket_id))?;
+    }
+    Ok(Cascade { total, copies: out })
+}
+
+// Without wrapping every win is of a later place, so one pass in place order is enough
+fn copies_in_order(
+    wins: &[Vec<usize>],
+    place_id: impl Fn(usize) -> i32,
+) -> Result<Vec<usize>, CascadeError> {
+    let mut copies: Vec<usize> = vec![1; wins.len()];
+    for place in 0..wins.len() {
+        for won in &wins[place] {
+            copies[*won] = copies[*won]
+                .checked_add(copies[place])
+                .ok_or(CascadeError::Overflow(place_id(*won)))?;
+        }
+    }
+    Ok(copies)
+}
+
+// Wrapping around lets wins flow backwards, so places are taken in an order drawn from
+// the win graph instead, and a cycle in it means the cascade never ends
+fn copies_by_graph(
+    wins: &[Vec<usize>],
+    place_id: impl Fn(usize) -> i32,
+) -> Result<Vec<usize>, CascadeError> {
+    let mut incoming = vec![0; wins.len()];
+    for won in wins.iter().flatten() {
+        incoming[*won] += 1;
+    }
+    let mut ready: Vec<usize> = (0..wins.len()).filter(|p| incoming[*p] == 0).collect();
+    let mut copies: Vec<usize> = vec![1; wins.len()];
+    while let Some(place) = ready.pop() {
+        for won in &wins[place] {
+            copies[*won] = copies[*won]
+                .checked_add(copies[place])
+                .ok_or(CascadeError::Overflow(place_id(*won)))?;
+            incoming[*won] -= 1;
+            if incoming[*won] == 0 {
+                ready.push(*won);
+            }
+        }
+    }
+    match on_cycle(wins, &incoming) {
+        Some(place) => Err(CascadeError::Infinite(place_id(place))),
+        None => Ok(copies),
+    }
+}
+
+// Places still waiting on wins once the graph is drained all lie on or after a cycle.
+// Stepping back through such places enough times is sure to land on the cycle itself
+fn on_cycle(wins: &[Vec<usize>], incoming: &[usize]) -> Option<usize> {
+    let mut place = incoming.iter().position(|n| *n > 0)?;
+    let mut winners: Vec<Vec<usize>> = vec![Vec::new(); wins.len()];
+    for (winner, won) in wins.iter().enumerate() {
+        for place in won {
+            winners[*place].push(winner);
+        }
+    }
+    for _ in 0..wins.len() {
+        place = *winners[place].iter().find(|w| incoming[**w] > 0)?;
+    }
+    Some(place)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::cascade::{cascade, PastLastCard};
+    use crate::error::CascadeError;
+    use crate::scoring::{Doubling, Linear};
+    use crate::ticket::Ticket;
+
+    fn example() -> Vec<Ticket> {
+        [
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53",
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19",
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1",
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83",
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36",
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11",
+        ]
+        .iter()
+        .map(|l| Ticket::from_string(l.to_string()).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn test_cascade_example() {
+        let result = cascade(&example(), PastLastCard::Error).unwrap();
+        assert_eq!(result.copies, vec![1, 2, 4, 8, 14, 1]);
+        assert_eq!(result.total, 30);
+    }
+
+    #[test]
+    fn test_cascade_worth() {
+        let tickets = example();
+        let result = cascade(&tickets, PastLastCard::Error).unwrap();
+        assert_eq!(result.worth(&tickets, &Doubling), Ok(28));
+        assert_eq!(result.worth(&tickets, &Linear), Ok(24));
+    }
+
+    #[test]
+    fn test_cascade_out_of_order() {
+        let mut tickets = example();
+        tickets.reverse();
+        let result = cascade(&tickets, PastLastCard::Error).unwrap();
+        assert_eq!(result.copies, vec![1, 14, 8, 4, 2, 1]);
+        assert_eq!(result.total, 30);
+    }
+
+    fn deck(lines: &[&str]) -> Vec<Ticket> {
+        lines
+            .iter()
+            .map(|l| Ticket::from_string(l.to_string()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_past_last_card() {
+        let tickets = deck(&["Card 1: 1 | 2", "Card 2: 1 2 | 1 2"]);
+        assert_eq!(
+            cascade(&tickets, PastLastCard::Error),
+            Err(CascadeError::PastLastCard(2, 2))
+        );
+        let result = cascade(&tickets, PastLastCard::Clamp).unwrap();
+        assert_eq!(result.copies, vec![1, 1]);
+    }
+
+    #[test]
+    fn test_overflow() {
+        let lines: Vec<String> = (1..=200)
+            .map(|id| format!("Card {}: 1 2 3 4 5 | 1 2 3 4 5", id))
+            .collect();
+        let lines: Vec<&str> = lines.iter().map(|l| l.as_str()).collect();
+        let result = cascade(&deck(&lines), PastLastCard::Clamp);
+        assert!(matches!(result, Err(CascadeError::Overflow(_))));
+    }
+
+    #[test]
+    fn test_worth_overflow() {
+        let lines: Vec<String> = (1..=64)
+            .map(|id| format!("Card {}: 1 2 3 4 5 | 1 2 3 4 5", id))
+            .collect();
+        let lines: Vec<&str> = lines.iter().map(|l| l.as_str()).collect();
+        let tickets = deck(&lines);
+        let result = cascade(&tickets, PastLastCard::Clamp).unwrap();
+        assert!(matches!(
+            result.worth(&tickets, &Doubling),
+            Err(CascadeError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_wrap() {
+        let tickets = deck(&["Card 1: 1 | 2", "Card 2: 1 | 2", "Card 3: 1 | 1"]);
+        let result = cascade(&tickets, PastLastCard::Wrap).unwrap();
+        assert_eq!(result.copies, vec![2, 1, 1]);
+        assert_eq!(result.total, 4);
+    }
+
+    #[test]
+    fn test_wrap_cycle() {
+        let tickets = deck(&["Card 1: 1 | 2", "Card 2: 1 | 1", "Card 3: 1 2 | 1 2"]);
+        assert_eq!(
+            cascade(&tickets, PastLastCard::Wrap),
+            Err(CascadeError::Infinite(3))
+        );
+    }
+}
@@ -0,0 +1,46 @@
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TicketParseError {
+    MissingHeader,
+    MissingBar,
+    BadNumber(String),
+}
+
+impl Display for TicketParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TicketParseError::MissingHeader => write!(f, "missing \"Card N:\" header"),
+            TicketParseError::MissingBar => write!(f, "missing '|' between the two lists"),
+            TicketParseError::BadNumber(text) => write!(f, "{:?} is not a number", text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum CascadeError {
+    // The card and the number of cards past the last one it would win
+    PastLastCard(i32, usize),
+    // A card that wins a copy of itself, directly or through other cards
+    Infinite(i32),
+    // A card whose copies, or whose share of a total, no longer fit in a count
+    Overflow(i32),
+}
+
+impl Display for CascadeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CascadeError::PastLastCard(card, past) => write!(
+                f,
+                "card {} wins copies of {} cards past the last card",
+                card, past
+            ),
+            CascadeError::Infinite(card) => {
+                write!(f, "card {} wins copies of itself without end", card)
+            }
+            CascadeError::Overflow(card) => {
+                write!(f, "card {} takes the count past what fits", card)
+            }
+        }
+    }
+}
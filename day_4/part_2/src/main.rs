@@ -1,24 +1,69 @@
-use std::collections::HashMap;
-use crate::ticket::Ticket;
+use crate::cascade::{cascade, PastLastCard};
+use crate::scoring::Scoring;
+use crate::ticket::{Duplicates, Ticket};
+use clap::Parser;
 use std::io::stdin;
+use std::process;
 
+mod cascade;
+mod error;
+mod scoring;
 mod ticket;
 
+#[derive(Parser)]
+struct Args {
+    /// Whether a drawn number repeated on a card matches every time or only once
+    #[arg(long, value_enum, default_value_t = Duplicates::EveryTime)]
+    duplicates: Duplicates,
+    /// What happens when a card wins copies of cards past the last one
+    #[arg(long, value_enum, default_value_t = PastLastCard::Error)]
+    past_end: PastLastCard,
+    /// How a card's matches turn into points: doubling, linear, fibonacci or table:1,3,5
+    #[arg(long, default_value_t = Scoring::Doubling)]
+    scoring: Scoring,
+}
+
 fn main() {
-    let tickets: HashMap<i32,Ticket> = stdin()
-        .lines()
-        .filter_map(Result::ok)
-        .filter(|l| l.len() > 0)
-        .map(|l| Ticket::from_string(l))
-        .map(|t| (t.ticket_id, t)).collect();
-    let mut queue: Vec<&Ticket> = tickets.values().map(|t| t).collect();
-    let mut n = 0;
-    while !queue.is_empty() {
-        let current = queue.pop().unwrap();
-        n +=1;
-        for i in (current.ticket_id+1)..(current.ticket_id +1 + current.get_winning_numbers().len() as i32){
-            queue.push(&tickets[&i]);
+    let args = Args::parse();
+    let tickets = read_tickets(args.duplicates);
+    let cascade = cascade(&tickets, args.past_end).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    let rule = args.scoring.rule();
+    for (ticket, copies) in tickets.iter().zip(&cascade.copies) {
+        println!(
+            "Card {}: {} copies, {} matches worth {}",
+            ticket.ticket_id,
+            copies,
+            ticket.get_winning_numbers().len(),
+            ticket.calculate_worth(rule.as_ref())
+        );
+    }
+    let worth = cascade.worth(&tickets, rule.as_ref()).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1)
+    });
+    println!("Worth of every copy: {}", worth);
+    println!("{}", cascade.total);
+}
+
+// A malformed line is reported and skipped rather than ending the run, but a line that
+// cannot be read at all ends it, since the card count would be wrong from there on
+fn read_tickets(duplicates: Duplicates) -> Vec<Ticket> {
+    let mut tickets = Vec::new();
+    for (index, line) in stdin().lines().enumerate() {
+        let line = line.unwrap_or_else(|e| {
+            eprintln!("line {}: {}", index + 1, e);
+            process::exit(1)
+        });
+        if line.is_empty() {
+            continue;
+        }
+        match Ticket::from_string(line) {
+            Ok(ticket) => tickets.push(ticket.with_duplicates(duplicates)),
+            Err(e) => eprintln!("line {}: {}, skipped", index + 1, e),
         }
     }
-    println!("{}", n);
+    tickets
 }